@@ -1,3 +1,9 @@
+// Positions and some nodes are only kept for diagnostics,
+// and boxing every expression would make the grammar actions noisy.
+#![allow(dead_code, clippy::large_enum_variant)]
+
+use std::fmt;
+
 #[derive(Debug)]
 pub struct Position(pub usize, pub usize);
 
@@ -17,6 +23,9 @@ pub struct Ident {
 pub enum GlobalItem {
     Decl(Decl),
     FuncDef(FuncDef),
+    Struct(StructDef),
+    /// A global item that failed to parse.
+    Error(Position),
 }
 
 #[derive(Debug)]
@@ -34,6 +43,7 @@ pub struct ConstDecl {
 #[derive(Debug)]
 pub struct ConstDef {
     pub id: Ident,
+    pub ty: Type,
     pub dims: Vec<ConstExpr>,
     pub init: ConstInitVal,
     pub pos: Position,
@@ -54,6 +64,7 @@ pub struct VarDecl {
 #[derive(Debug)]
 pub struct VarDef {
     pub id: Ident,
+    pub ty: Option<Type>,
     pub dims: Vec<ConstExpr>,
     pub init: Option<InitVal>,
     pub pos: Position,
@@ -65,6 +76,22 @@ pub enum InitVal {
     List(Vec<InitVal>),
 }
 
+/// `struct Name { a: Int, b: Int[4] }`, fields are laid out in order.
+#[derive(Debug)]
+pub struct StructDef {
+    pub id: Ident,
+    pub fields: Vec<FieldDef>,
+    pub pos: Position,
+}
+
+#[derive(Debug)]
+pub struct FieldDef {
+    pub id: Ident,
+    pub ty: Type,
+    pub dims: Vec<ConstExpr>,
+    pub pos: Position,
+}
+
 #[derive(Debug)]
 pub struct FuncDef {
     pub ty: Type,
@@ -78,6 +105,25 @@ pub struct FuncDef {
 pub enum Type {
     Unit(Position),
     Int(Position),
+    Bool(Position),
+    /// A fixed-width integer type.
+    Sized(IntTy, Position),
+    /// A struct type, by its name.
+    Struct(Ident),
+    /// `*T`, a pointer to a value of type `T`.
+    Pointer(Box<Type>, Position),
+}
+
+/// Fixed-width integer types, `Int` is a signed 32-bit integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntTy {
+    I8,
+    I16,
+    U8,
+    U32,
+    /// A signed 64-bit integer stored in two words, low word first.
+    /// `I64` values can not be divided and are never constant expressions.
+    I64,
 }
 
 #[derive(Debug)]
@@ -104,13 +150,22 @@ pub enum BlockItem {
 #[derive(Debug)]
 pub enum Stmt {
     Assign(Assign),
+    CompoundAssign(CompoundAssign),
+    Increment(Increment),
     ExprStmt(ExprStmt),
     Block(Block),
     If(Box<If>),
     While(Box<While>),
+    For(Box<For>),
+    ForRange(Box<ForRange>),
+    Loop(Box<Loop>),
+    DoWhile(Box<DoWhile>),
+    Match(Box<Match>),
     Break(Break),
     Continue(Continue),
     Return(Return),
+    /// A statement that failed to parse.
+    Error(Position),
 }
 
 #[derive(Debug)]
@@ -120,6 +175,22 @@ pub struct Assign {
     pub pos: Position,
 }
 
+#[derive(Debug)]
+pub struct CompoundAssign {
+    pub lval: LVal,
+    pub op: CompoundOp,
+    pub exp: Expr,
+    pub pos: Position,
+}
+
+/// `lval++` or `lval--`.
+#[derive(Debug)]
+pub struct Increment {
+    pub lval: LVal,
+    pub op: IncOp,
+    pub pos: Position,
+}
+
 #[derive(Debug)]
 pub struct ExprStmt {
     pub exp: Option<Expr>,
@@ -136,18 +207,115 @@ pub struct If {
 
 #[derive(Debug)]
 pub struct While {
+    pub label: Option<Label>,
     pub cond: Expr,
     pub body: Stmt,
     pub pos: Position,
 }
 
+/// `for (init; cond; step) body`, all of `init`, `cond` and `step` are optional.
+#[derive(Debug)]
+pub struct For {
+    pub label: Option<Label>,
+    pub init: Option<BlockItem>,
+    pub cond: Option<Expr>,
+    pub step: Option<Stmt>,
+    pub body: Stmt,
+    pub pos: Position,
+}
+
+/// `for id in start..end body`.
+#[derive(Debug)]
+pub struct ForRange {
+    pub label: Option<Label>,
+    pub id: Ident,
+    pub start: Expr,
+    pub end: Expr,
+    pub body: Block,
+    pub pos: Position,
+}
+
+/// `loop body`, runs until a `break` or `return`.
+#[derive(Debug)]
+pub struct Loop {
+    pub label: Option<Label>,
+    pub body: Block,
+    pub pos: Position,
+}
+
+/// `do body while (cond);`, checks the condition after each iteration.
+#[derive(Debug)]
+pub struct DoWhile {
+    pub label: Option<Label>,
+    pub body: Block,
+    pub cond: Expr,
+    pub pos: Position,
+}
+
+/// `match (exp) { arms }`, the first arm with a matching pattern runs.
+#[derive(Debug)]
+pub struct Match {
+    pub exp: Expr,
+    pub arms: Vec<MatchArm>,
+    pub pos: Position,
+}
+
+/// `pat | pat ... => body`.
+#[derive(Debug)]
+pub struct MatchArm {
+    pub pats: Vec<Pattern>,
+    pub body: Stmt,
+    pub pos: Position,
+}
+
+impl MatchArm {
+    /// Returns `true` if the arm matches all values.
+    pub fn is_wildcard(&self) -> bool {
+        self.pats.iter().any(|p| matches!(p, Pattern::Wildcard(_)))
+    }
+}
+
+#[derive(Debug)]
+pub enum Pattern {
+    Value(PatValue),
+    /// `lo..=hi`, both bounds are inclusive.
+    Range(PatValue, PatValue),
+    Wildcard(Position),
+}
+
+/// Integer literal with an optional sign, or the name of a constant.
+#[derive(Debug)]
+pub enum PatValue {
+    Number(Number),
+    Const(Ident),
+}
+
+impl PatValue {
+    /// Returns the position of the value.
+    pub fn pos(&self) -> &Position {
+        match self {
+            Self::Number(num) => &num.pos,
+            Self::Const(id) => &id.pos,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Break {
+    pub label: Option<Label>,
     pub pos: Position,
 }
 
 #[derive(Debug)]
 pub struct Continue {
+    pub label: Option<Label>,
+    pub pos: Position,
+}
+
+/// Loop label like `'outer`, the name does not include the quote.
+#[derive(Debug)]
+pub struct Label {
+    pub name: String,
     pub pos: Position,
 }
 
@@ -163,8 +331,39 @@ pub struct Expr {
     pub pos: Position,
 }
 
+impl Expr {
+    /// Creates an expression consisting of only the given primary expression.
+    pub fn from_primary(exp: PrimaryExpr, pos: Position) -> Self {
+        let exp = MulExpr::Unary(UnaryExpr::Primary(exp));
+        let exp = RelExpr::Shift(ShiftExpr::Add(AddExpr::Mul(exp)));
+        let exp = BitXorExpr::BitAnd(BitAndExpr::Eq(EqExpr::Rel(exp)));
+        let lor = LOrExpr::LAnd(LAndExpr::BitOr(BitOrExpr::BitXor(exp)));
+        Self { lor, pos }
+    }
+}
+
 #[derive(Debug)]
 pub struct LVal {
+    /// Number of leading `*`, applied after indices and fields.
+    pub derefs: usize,
+    pub base: LValBase,
+    pub indices: Vec<Expr>,
+    pub fields: Vec<FieldAccess>,
+    pub pos: Position,
+}
+
+/// The value the indices, fields and dereferences of a left value apply to.
+#[derive(Debug)]
+pub enum LValBase {
+    /// A variable or a constant.
+    Var(Ident),
+    /// An expression, like `p + 1` in `*(p + 1)` or `*p` in `(*p).x`.
+    Expr(Box<UnaryExpr>),
+}
+
+/// `.name[i]...` after a left value.
+#[derive(Debug)]
+pub struct FieldAccess {
     pub id: Ident,
     pub indices: Vec<Expr>,
     pub pos: Position,
@@ -175,6 +374,42 @@ pub enum PrimaryExpr {
     Expr(Box<Expr>),
     LVal(LVal),
     Number(Number),
+    Bool(Bool),
+    Str(Str),
+    If(Box<IfExpr>),
+}
+
+/// `if (cond) { ... then } else { ... else_then }`, the `else` is required.
+#[derive(Debug)]
+pub struct IfExpr {
+    pub cond: Expr,
+    pub then: ValueBlock,
+    pub else_then: ElseExpr,
+    pub pos: Position,
+}
+
+#[derive(Debug)]
+pub enum ElseExpr {
+    Block(ValueBlock),
+    If(Box<IfExpr>),
+}
+
+impl ElseExpr {
+    /// Returns the position of the expression that gives the value.
+    pub fn value_pos(&self) -> &Position {
+        match self {
+            Self::Block(block) => &block.value.pos,
+            Self::If(exp) => &exp.pos,
+        }
+    }
+}
+
+/// Block that ends in a trailing expression, which is the value of the block.
+#[derive(Debug)]
+pub struct ValueBlock {
+    pub items: Vec<BlockItem>,
+    pub value: Expr,
+    pub pos: Position,
 }
 
 #[derive(Debug)]
@@ -183,11 +418,29 @@ pub struct Number {
     pub pos: Position,
 }
 
+/// `true` or `false`.
+#[derive(Debug)]
+pub struct Bool {
+    pub value: bool,
+    pub pos: Position,
+}
+
+/// String literal, the value is the decoded bytes without the terminating zero.
+#[derive(Debug)]
+pub struct Str {
+    pub value: Vec<u8>,
+    pub pos: Position,
+}
+
 #[derive(Debug)]
 pub enum UnaryExpr {
     Primary(PrimaryExpr),
     Call(FuncCall),
     Unary(UnaryOp, Box<UnaryExpr>),
+    /// `&lval`, the address of a left value.
+    AddrOf(LVal),
+    /// `exp as T`, an explicit conversion.
+    Cast(Box<UnaryExpr>, Type),
 }
 
 #[derive(Debug)]
@@ -210,9 +463,15 @@ pub enum AddExpr {
 }
 
 #[derive(Debug)]
-pub enum RelExpr {
+pub enum ShiftExpr {
     Add(AddExpr),
-    RelAdd(Box<RelExpr>, RelOp, AddExpr),
+    ShiftAdd(Box<ShiftExpr>, ShiftOp, AddExpr),
+}
+
+#[derive(Debug)]
+pub enum RelExpr {
+    Shift(ShiftExpr),
+    RelShift(Box<RelExpr>, RelOp, ShiftExpr),
 }
 
 #[derive(Debug)]
@@ -222,9 +481,27 @@ pub enum EqExpr {
 }
 
 #[derive(Debug)]
-pub enum LAndExpr {
+pub enum BitAndExpr {
     Eq(EqExpr),
-    LAndEq(Box<LAndExpr>, EqExpr),
+    BitAndEq(Box<BitAndExpr>, EqExpr),
+}
+
+#[derive(Debug)]
+pub enum BitXorExpr {
+    BitAnd(BitAndExpr),
+    BitXorBitAnd(Box<BitXorExpr>, BitAndExpr),
+}
+
+#[derive(Debug)]
+pub enum BitOrExpr {
+    BitXor(BitXorExpr),
+    BitOrBitXor(Box<BitOrExpr>, BitXorExpr),
+}
+
+#[derive(Debug)]
+pub enum LAndExpr {
+    BitOr(BitOrExpr),
+    LAndBitOr(Box<LAndExpr>, BitOrExpr),
 }
 
 #[derive(Debug)]
@@ -243,6 +520,7 @@ pub struct ConstExpr {
 pub enum UnaryOp {
     Neg(Position),
     LNot(Position),
+    Not(Position),
 }
 
 #[derive(Debug)]
@@ -258,6 +536,34 @@ pub enum AddOp {
     Sub(Position),
 }
 
+#[derive(Debug)]
+pub enum ShiftOp {
+    Shl(Position),
+    Sar(Position),
+    Shr(Position),
+}
+
+#[derive(Debug)]
+pub enum CompoundOp {
+    Add(Position),
+    Sub(Position),
+    Mul(Position),
+    Div(Position),
+    Mod(Position),
+    And(Position),
+    Or(Position),
+    Xor(Position),
+    Shl(Position),
+    Sar(Position),
+    Shr(Position),
+}
+
+#[derive(Debug)]
+pub enum IncOp {
+    Inc(Position),
+    Dec(Position),
+}
+
 #[derive(Debug)]
 pub enum RelOp {
     Lt(Position),
@@ -272,29 +578,163 @@ pub enum EqOp {
     Ne(Position),
 }
 
-impl Type {
-    pub fn to_string(&self) -> String {
+/// Trait for getting the position of an expression, the levels of binary
+/// expressions do not store theirs, so it is computed from the operands.
+pub trait Span {
+    fn span(&self) -> Position;
+}
+
+impl Span for Expr {
+    fn span(&self) -> Position {
+        Position(self.pos.0, self.pos.1)
+    }
+}
+
+impl Span for LVal {
+    fn span(&self) -> Position {
+        Position(self.pos.0, self.pos.1)
+    }
+}
+
+impl Span for PrimaryExpr {
+    fn span(&self) -> Position {
+        let pos = match self {
+            Self::Expr(exp) => &exp.pos,
+            Self::LVal(lval) => &lval.pos,
+            Self::Number(num) => &num.pos,
+            Self::Bool(b) => &b.pos,
+            Self::Str(s) => &s.pos,
+            Self::If(exp) => &exp.pos,
+        };
+        Position(pos.0, pos.1)
+    }
+}
+
+impl Span for IfExpr {
+    fn span(&self) -> Position {
+        Position(self.pos.0, self.pos.1)
+    }
+}
+
+impl Span for ValueBlock {
+    fn span(&self) -> Position {
+        Position(self.pos.0, self.pos.1)
+    }
+}
+
+impl Span for UnaryExpr {
+    fn span(&self) -> Position {
+        match self {
+            Self::Primary(exp) => exp.span(),
+            Self::Call(call) => Position(call.pos.0, call.pos.1),
+            Self::Unary(op, exp) => Position(op.pos().0, exp.span().1),
+            Self::AddrOf(lval) => lval.span(),
+            Self::Cast(exp, ty) => Position(exp.span().0, ty.pos().1),
+        }
+    }
+}
+
+/// Implements `Span` for a level of binary expressions.
+macro_rules! impl_span {
+    ($ty:ident, $lower:ident, $binary:ident) => {
+        impl Span for $ty {
+            fn span(&self) -> Position {
+                match self {
+                    Self::$lower(exp) => exp.span(),
+                    Self::$binary(lhs, .., rhs) => Position(lhs.span().0, rhs.span().1),
+                }
+            }
+        }
+    };
+}
+
+impl_span!(MulExpr, Unary, MulUnary);
+impl_span!(AddExpr, Mul, AddMul);
+impl_span!(ShiftExpr, Add, ShiftAdd);
+impl_span!(RelExpr, Shift, RelShift);
+impl_span!(EqExpr, Rel, EqRel);
+impl_span!(BitAndExpr, Eq, BitAndEq);
+impl_span!(BitXorExpr, BitAnd, BitXorBitAnd);
+impl_span!(BitOrExpr, BitXor, BitOrBitXor);
+impl_span!(LAndExpr, BitOr, LAndBitOr);
+impl_span!(LOrExpr, LAnd, LOrLAnd);
+
+impl UnaryOp {
+    pub fn pos(&self) -> &Position {
         match self {
-            Type::Int(_) => "Int",
-            Type::Unit(_) => "Unit",
+            UnaryOp::Neg(pos) | UnaryOp::LNot(pos) | UnaryOp::Not(pos) => pos,
         }
-        .to_string()
     }
+}
 
+impl Type {
     pub fn pos(&self) -> &Position {
         match self {
             Type::Int(pos) => pos,
             Type::Unit(pos) => pos,
+            Type::Bool(pos) => pos,
+            Type::Sized(_, pos) => pos,
+            Type::Struct(id) => &id.pos,
+            Type::Pointer(_, pos) => pos,
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Int(_) => write!(f, "Int"),
+            Type::Unit(_) => write!(f, "Unit"),
+            Type::Bool(_) => write!(f, "Bool"),
+            Type::Sized(ty, _) => write!(f, "{}", ty),
+            Type::Struct(id) => write!(f, "{}", id.name),
+            Type::Pointer(ty, _) => write!(f, "*{}", ty),
+        }
+    }
+}
+
+impl IntTy {
+    /// Returns the size of values of the type in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            IntTy::I8 | IntTy::U8 => 1,
+            IntTy::I16 => 2,
+            IntTy::U32 => 4,
+            IntTy::I64 => 8,
         }
     }
 
-    pub fn to_string(types: &Vec<Type>) -> String {
-        let mut types_str = "(";
+    /// Returns `true` if the type is signed.
+    pub fn is_signed(self) -> bool {
+        !matches!(self, IntTy::U8 | IntTy::U32)
+    }
 
-        for ty in types {
-            types_str += format!("=> {}", ty.to_string()).to_owned()
+    /// Truncates the given integer to the type, values of types narrower than
+    /// 32 bits are sign- or zero-extended. All integers are `I64` values.
+    pub fn wrap(self, value: i32) -> i32 {
+        match self {
+            IntTy::I8 => value as i8 as i32,
+            IntTy::I16 => value as i16 as i32,
+            IntTy::U8 => value as u8 as i32,
+            IntTy::U32 | IntTy::I64 => value,
         }
+    }
+
+    /// Returns `true` if the given integer is a value of the type,
+    /// all integers are `U32` values by their bit patterns.
+    pub fn contains(self, value: i32) -> bool {
+        self.wrap(value) == value
+    }
+}
 
-        types_str.to_string()
+impl fmt::Display for IntTy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IntTy::I8 => write!(f, "I8"),
+            IntTy::I16 => write!(f, "I16"),
+            IntTy::U8 => write!(f, "U8"),
+            IntTy::U32 => write!(f, "U32"),
+            IntTy::I64 => write!(f, "I64"),
+        }
     }
 }
@@ -0,0 +1,204 @@
+use codespan_reporting::term::termcolor::ColorChoice;
+use ophelia::ir::interp;
+use ophelia::{Diagnostics, Emit, Options, Output};
+use std::fs::{read_to_string, File};
+use std::io::{self, stderr, stdin, stdout, IsTerminal, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::str::FromStr;
+use structopt::StructOpt;
+
+/// Command line options of the compiler.
+#[derive(StructOpt)]
+#[structopt(
+    name = "ophelia",
+    about = "A minimalist imperative language compiled to RISC-V"
+)]
+pub struct Opts {
+    /// Kind of output to emit: `ast`, `koopa` or `riscv`.
+    #[structopt(long, default_value = "riscv", possible_values = &["ast", "koopa", "riscv"])]
+    pub emit: Emit,
+
+    /// Output file, writes to stdout if omitted.
+    #[structopt(short, long, parse(from_os_str))]
+    pub output: Option<PathBuf>,
+
+    /// When to colorize diagnostics: `auto`, `always` or `never`.
+    #[structopt(long, default_value = "auto", possible_values = &["auto", "always", "never"])]
+    pub color: Color,
+
+    /// Source file, reads from stdin if omitted or `-`.
+    #[structopt(parse(from_os_str))]
+    pub input: Option<PathBuf>,
+
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+}
+
+// no doc comment, structopt would use it as the description of the program
+#[derive(StructOpt)]
+pub enum Command {
+    /// Compiles the source file and runs it with the Koopa IR interpreter,
+    /// exits with the return value of `main`.
+    Run {
+        /// Source file.
+        #[structopt(parse(from_os_str))]
+        input: PathBuf,
+    },
+}
+
+/// When to colorize diagnostics.
+#[derive(Clone, Copy)]
+pub enum Color {
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            _ => Err(format!("unknown color choice `{}`", s)),
+        }
+    }
+}
+
+impl From<Color> for ColorChoice {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::Auto if stderr().is_terminal() => ColorChoice::Auto,
+            Color::Auto | Color::Never => ColorChoice::Never,
+            Color::Always => ColorChoice::Always,
+        }
+    }
+}
+
+/// Exit status when the source program has errors.
+const EXIT_COMPILE_ERROR: u8 = 1;
+/// Exit status when the input or output file can not be accessed.
+const EXIT_IO_ERROR: u8 = 2;
+/// Exit status when the interpreted program fails at runtime.
+const EXIT_RUNTIME_ERROR: u8 = 3;
+
+/// Possible errors of the driver.
+enum Error {
+    /// Diagnostics have already been reported.
+    Compile,
+    Io(String, io::Error),
+    Runtime(interp::Error),
+}
+
+impl Opts {
+    /// Parses the command line arguments of the current process.
+    ///
+    /// The single-dash modes `-ast`, `-koopa` and `-riscv` are accepted
+    /// as aliases of `--emit`, e.g. `ophelia -koopa hello.oph -o hello.koopa`.
+    pub fn from_env() -> Self {
+        Self::from_iter(std::env::args().map(|arg| match arg.as_str() {
+            "-ast" | "-koopa" | "-riscv" => format!("--emit={}", &arg[1..]),
+            _ => arg,
+        }))
+    }
+}
+
+/// Runs the compiler with the given options, returns the exit status.
+pub fn run(opts: Opts) -> ExitCode {
+    let result = match &opts.command {
+        Some(Command::Run { input }) => interpret(&opts, input),
+        None => compile(&opts).map(|()| ExitCode::SUCCESS),
+    };
+    match result {
+        Ok(code) => code,
+        Err(Error::Compile) => ExitCode::from(EXIT_COMPILE_ERROR),
+        Err(Error::Io(path, e)) => {
+            eprintln!("error: {}: {}", path, e);
+            ExitCode::from(EXIT_IO_ERROR)
+        }
+        Err(Error::Runtime(e)) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(EXIT_RUNTIME_ERROR)
+        }
+    }
+}
+
+fn compile(opts: &Opts) -> Result<(), Error> {
+    let (file_name, source) = read_input(opts.input.as_ref())?;
+    let options = Options {
+        emit: opts.emit,
+        file_name,
+        color: ColorChoice::from(opts.color),
+    };
+    let output = ophelia::compile(&source, options).map_err(compile_error)?;
+    let mut content = Vec::new();
+    output
+        .write_to(&mut content)
+        .map_err(|e| Error::Io(String::from("<output>"), e))?;
+    write_output(opts.output.as_ref(), &content)
+}
+
+fn interpret(opts: &Opts, input: &PathBuf) -> Result<ExitCode, Error> {
+    let (file_name, source) = read_input(Some(input))?;
+    let options = Options {
+        emit: Emit::Koopa,
+        file_name,
+        color: ColorChoice::from(opts.color),
+    };
+    let Output::Koopa(program) = ophelia::compile(&source, options).map_err(compile_error)? else {
+        unreachable!()
+    };
+    let ret =
+        interp::run(&program, &mut stdin().lock(), &mut stdout().lock()).map_err(Error::Runtime)?;
+    Ok(ExitCode::from(ret as u8))
+}
+
+/// Emits the diagnostics followed by the error count summary,
+/// returns the error of the driver.
+fn compile_error(diagnostics: Diagnostics) -> Error {
+    diagnostics.emit();
+    let name = diagnostics.file_name();
+    match diagnostics.errors().len() {
+        1 => eprintln!("error: could not compile `{}` due to previous error", name),
+        count => eprintln!(
+            "error: could not compile `{}` due to {} previous errors",
+            name, count
+        ),
+    }
+    Error::Compile
+}
+
+/// Reads the source file, or stdin if no file is given.
+/// Returns the file name and the source code.
+fn read_input(input: Option<&PathBuf>) -> Result<(String, String), Error> {
+    match input {
+        Some(path) if path.as_os_str() != "-" => {
+            let file = path.display().to_string();
+            let source = read_to_string(path).map_err(|e| Error::Io(file.clone(), e))?;
+            Ok((file, source))
+        }
+        _ => {
+            let file = String::from("<stdin>");
+            let mut source = String::new();
+            stdin()
+                .read_to_string(&mut source)
+                .map_err(|e| Error::Io(file.clone(), e))?;
+            Ok((file, source))
+        }
+    }
+}
+
+/// Writes the output to the given file, or stdout if no file is given.
+fn write_output(output: Option<&PathBuf>, content: &[u8]) -> Result<(), Error> {
+    match output {
+        Some(path) => File::create(path)
+            .and_then(|mut f| f.write_all(content))
+            .map_err(|e| Error::Io(path.display().to_string(), e)),
+        None => stdout()
+            .write_all(content)
+            .map_err(|e| Error::Io(String::from("<stdout>"), e)),
+    }
+}
@@ -1,16 +1,15 @@
-use super::fun::FunctionInfo;
-use std::fs::File;
+use super::func::FunctionInfo;
 use std::io::{Result, Write};
 
 /// Assembly builder.
 pub struct AsmBuilder<'f> {
-    f: &'f mut File,
+    f: &'f mut dyn Write,
     temp: &'static str,
 }
 
 impl<'f> AsmBuilder<'f> {
     /// Creates a new assembly builder.
-    pub fn new(f: &'f mut File, temp: &'static str) -> Self {
+    pub fn new(f: &'f mut dyn Write, temp: &'static str) -> Self {
         Self { f, temp }
     }
 
@@ -69,20 +68,30 @@ impl<'f> AsmBuilder<'f> {
     }
 
     pub fn sw(&mut self, src: &str, addr: &str, offset: i32) -> Result<()> {
+        self.store("sw", src, addr, offset)
+    }
+
+    pub fn lw(&mut self, dest: &str, addr: &str, offset: i32) -> Result<()> {
+        self.load("lw", dest, addr, offset)
+    }
+
+    /// Generates a store instruction (`sw`, `sh` or `sb`).
+    pub fn store(&mut self, op: &str, src: &str, addr: &str, offset: i32) -> Result<()> {
         if (-2048..=2047).contains(&offset) {
-            writeln!(self.f, "  sw {src}, {offset}({addr})")
+            writeln!(self.f, "  {op} {src}, {offset}({addr})")
         } else {
             self.addi(self.temp, addr, offset)?;
-            writeln!(self.f, "  sw {src}, 0({})", self.temp)
+            writeln!(self.f, "  {op} {src}, 0({})", self.temp)
         }
     }
 
-    pub fn lw(&mut self, dest: &str, addr: &str, offset: i32) -> Result<()> {
+    /// Generates a load instruction (`lw`, `lh`, `lhu`, `lb` or `lbu`).
+    pub fn load(&mut self, op: &str, dest: &str, addr: &str, offset: i32) -> Result<()> {
         if (-2048..=2047).contains(&offset) {
-            writeln!(self.f, "  lw {dest}, {offset}({addr})")
+            writeln!(self.f, "  {op} {dest}, {offset}({addr})")
         } else {
             self.addi(self.temp, addr, offset)?;
-            writeln!(self.f, "  lw {dest}, 0({})", self.temp)
+            writeln!(self.f, "  {op} {dest}, 0({})", self.temp)
         }
     }
 
@@ -90,10 +99,18 @@ impl<'f> AsmBuilder<'f> {
         writeln!(self.f, "  bnez {cond}, {label}")
     }
 
+    pub fn bgeu(&mut self, lhs: &str, rhs: &str, label: &str) -> Result<()> {
+        writeln!(self.f, "  bgeu {lhs}, {rhs}, {label}")
+    }
+
     pub fn j(&mut self, label: &str) -> Result<()> {
         writeln!(self.f, "  j {label}")
     }
 
+    pub fn jr(&mut self, addr: &str) -> Result<()> {
+        writeln!(self.f, "  jr {addr}")
+    }
+
     pub fn call(&mut self, fun: &str) -> Result<()> {
         writeln!(self.f, "  call {fun}")
     }
@@ -1,3 +1,4 @@
+use super::table::JumpTable;
 use koopa::ir::entities::ValueData;
 use koopa::ir::{BasicBlock, Function, TypeKind, ValueKind};
 use std::cell::Cell;
@@ -12,12 +13,14 @@ pub struct FunctionInfo {
     alloc_size: usize,
     allocs: HashMap<*const ValueData, Slot>,
     bbs: HashMap<BasicBlock, String>,
+    /// Jump tables by the basic blocks they replace.
+    tables: Vec<(BasicBlock, JumpTable)>,
     sp_offset: Cell<Option<usize>>,
 }
 
 impl FunctionInfo {
     thread_local! {
-      static NEXT_TEMP_LABEL_ID: Cell<usize> = Cell::new(0);
+      static NEXT_TEMP_LABEL_ID: Cell<usize> = const { Cell::new(0) };
     }
 
     /// Creates a new funtion information.
@@ -28,6 +31,7 @@ impl FunctionInfo {
             alloc_size: 0,
             allocs: HashMap::new(),
             bbs: HashMap::new(),
+            tables: Vec::new(),
             sp_offset: Cell::new(None),
         }
     }
@@ -96,6 +100,23 @@ impl FunctionInfo {
         self.bbs.get(&bb).as_ref().unwrap()
     }
 
+    /// Logs jump table, generates the label of the table.
+    pub fn log_jump_table(&mut self, bb: BasicBlock, mut table: JumpTable) {
+        let id = Self::NEXT_TEMP_LABEL_ID.with(|id| id.replace(id.get() + 1));
+        table.label = format!(".Ltable_{}", id);
+        self.tables.push((bb, table));
+    }
+
+    /// Returns the jump table that replaces the given basic block.
+    pub fn jump_table(&self, bb: BasicBlock) -> Option<&JumpTable> {
+        self.tables.iter().find(|(b, _)| *b == bb).map(|(_, t)| t)
+    }
+
+    /// Returns all jump tables in the funtion.
+    pub fn jump_tables(&self) -> impl Iterator<Item = &JumpTable> {
+        self.tables.iter().map(|(_, t)| t)
+    }
+
     /// Returns the stack pointer offset.
     pub fn sp_offset(&self) -> usize {
         if let Some(sp_offset) = self.sp_offset.get() {
@@ -111,7 +132,7 @@ impl FunctionInfo {
             // the final offset
             let offset = ra + self.alloc_size + args;
             // align to 16 bytes
-            let sp_offset = (offset + 15) / 16 * 16;
+            let sp_offset = offset.div_ceil(16) * 16;
             self.sp_offset.set(Some(sp_offset));
             sp_offset
         }
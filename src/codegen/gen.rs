@@ -1,18 +1,19 @@
 use super::builder::AsmBuilder;
-use super::fun::FunctionInfo;
+use super::func::FunctionInfo;
 use super::info::{current_fun, current_fun_mut, ProgramInfo};
+use super::table::{find_jump_tables, JumpTable};
 use super::values::{asm_value, AsmValue, LocalValue};
+use crate::ir::intrinsic::Intrinsic;
 use koopa::ir::entities::ValueData;
 use koopa::ir::values::*;
 use koopa::ir::{BasicBlock, Function, FunctionData, Program, TypeKind, Value, ValueKind};
-use std::fs::File;
 use std::io::{Result, Write};
 
 /// Trait for generating RISC-V assembly.
 pub trait GenerateToAsm<'p, 'i> {
     type Out;
 
-    fn generate(&self, f: &mut File, info: &'i mut ProgramInfo<'p>) -> Result<Self::Out>;
+    fn generate(&self, f: &mut dyn Write, info: &'i mut ProgramInfo<'p>) -> Result<Self::Out>;
 }
 
 /// Trait for generating RISC-V assembly (for values).
@@ -21,7 +22,7 @@ trait GenerateValueToAsm<'p, 'i> {
 
     fn generate(
         &self,
-        f: &mut File,
+        f: &mut dyn Write,
         info: &'i mut ProgramInfo<'p>,
         v: &ValueData,
     ) -> Result<Self::Out>;
@@ -30,12 +31,13 @@ trait GenerateValueToAsm<'p, 'i> {
 impl<'p, 'i> GenerateToAsm<'p, 'i> for Program {
     type Out = ();
 
-    fn generate(&self, f: &mut File, info: &mut ProgramInfo) -> Result<Self::Out> {
+    fn generate(&self, f: &mut dyn Write, info: &mut ProgramInfo) -> Result<Self::Out> {
         // generate global allocations
         for &value in self.inst_layout() {
             let data = self.borrow_value(value);
-            let name = &data.name().as_ref().unwrap()[1..];
-            info.insert_value(value, name.into());
+            let name = info
+                .insert_value(value, &data.name().as_ref().unwrap()[1..])
+                .to_string();
             writeln!(f, "  .data")?;
             writeln!(f, "  .globl {name}")?;
             writeln!(f, "{name}:")?;
@@ -43,9 +45,9 @@ impl<'p, 'i> GenerateToAsm<'p, 'i> for Program {
             writeln!(f)?;
         }
         // generate funtions
-        for &fun in self.fun_layout() {
+        for &fun in self.func_layout() {
             info.set_current_fun(FunctionInfo::new(fun));
-            self.fun(fun).generate(f, info)?;
+            self.func(fun).generate(f, info)?;
         }
         Ok(())
     }
@@ -54,20 +56,22 @@ impl<'p, 'i> GenerateToAsm<'p, 'i> for Program {
 impl<'p, 'i> GenerateToAsm<'p, 'i> for Function {
     type Out = &'p str;
 
-    fn generate(&self, _: &mut File, info: &mut ProgramInfo<'p>) -> Result<Self::Out> {
-        Ok(&info.program().fun(*self).name()[1..])
+    fn generate(&self, _: &mut dyn Write, info: &mut ProgramInfo<'p>) -> Result<Self::Out> {
+        Ok(&info.program().func(*self).name()[1..])
     }
 }
 
 impl<'p, 'i> GenerateToAsm<'p, 'i> for FunctionData {
     type Out = ();
 
-    fn generate(&self, f: &mut File, info: &mut ProgramInfo) -> Result<Self::Out> {
+    fn generate(&self, f: &mut dyn Write, info: &mut ProgramInfo) -> Result<Self::Out> {
         // skip declarations
         if self.layout().entry_bb().is_none() {
             return Ok(());
         }
-        // allocation stack slots and log argument number
+        // allocation stack slots and log argument number,
+        // intrinsics are not real calls
+        let program = info.program();
         let fun = current_fun_mut!(info);
         for value in self.dfg().values().values() {
             // allocate stack slot
@@ -76,7 +80,9 @@ impl<'p, 'i> GenerateToAsm<'p, 'i> for FunctionData {
             }
             // log argument number
             if let ValueKind::Call(call) = value.kind() {
-                fun.log_arg_num(call.args().len());
+                if intrinsic(program, call).is_none() {
+                    fun.log_arg_num(call.args().len());
+                }
             }
         }
         // generate basic block names
@@ -85,16 +91,34 @@ impl<'p, 'i> GenerateToAsm<'p, 'i> for FunctionData {
             assert!(data.params().is_empty());
             fun.log_bb_name(bb, data.name());
         }
+        // find compare chains that can be replaced by jump tables
+        for (bb, table) in find_jump_tables(self) {
+            fun.log_jump_table(bb, table);
+        }
         // generate prologue
         AsmBuilder::new(f, "t0").prologue(self.name(), fun)?;
         // generate instructions in basic blocks
         for (bb, node) in self.layout().bbs() {
             let name = bb.generate(f, info)?;
             writeln!(f, "{name}:")?;
+            if let Some(table) = current_fun!(info).jump_table(*bb) {
+                table.clone().generate(f, info)?;
+                continue;
+            }
             for &inst in node.insts().keys() {
                 self.dfg().value(inst).generate(f, info)?;
             }
         }
+        // generate jump table data
+        let fun = current_fun!(info);
+        for table in fun.jump_tables() {
+            writeln!(f, "  .data")?;
+            writeln!(f, "  .align 2")?;
+            writeln!(f, "{}:", table.label)?;
+            for &target in &table.targets {
+                writeln!(f, "  .word {}", fun.bb_name(target))?;
+            }
+        }
         writeln!(f)
     }
 }
@@ -102,20 +126,42 @@ impl<'p, 'i> GenerateToAsm<'p, 'i> for FunctionData {
 impl<'p, 'i> GenerateToAsm<'p, 'i> for BasicBlock {
     type Out = &'i str;
 
-    fn generate(&self, _: &mut File, info: &'i mut ProgramInfo) -> Result<Self::Out> {
+    fn generate(&self, _: &mut dyn Write, info: &'i mut ProgramInfo) -> Result<Self::Out> {
         Ok(current_fun!(info).bb_name(*self))
     }
 }
 
+impl<'p, 'i> GenerateToAsm<'p, 'i> for JumpTable {
+    type Out = ();
+
+    fn generate(&self, f: &mut dyn Write, info: &mut ProgramInfo) -> Result<Self::Out> {
+        self.value.generate(f, info)?.write_to(f, "t0")?;
+        let default = self.default.generate(f, info)?;
+        // jump to the default target if the index is out of range,
+        // negative indices are also out of range as unsigned integers
+        let mut builder = AsmBuilder::new(f, "t2");
+        builder.li("t1", self.min)?;
+        builder.op2("sub", "t0", "t0", "t1")?;
+        builder.li("t1", self.targets.len() as i32)?;
+        builder.bgeu("t0", "t1", default)?;
+        // load the target address from the table
+        builder.la("t1", &self.label)?;
+        builder.slli("t0", "t0", 2)?;
+        builder.op2("add", "t0", "t0", "t1")?;
+        builder.lw("t0", "t0", 0)?;
+        builder.jr("t0")
+    }
+}
+
 impl<'p, 'i> GenerateToAsm<'p, 'i> for Value {
     type Out = AsmValue<'i>;
 
-    fn generate(&self, _: &mut File, info: &'i mut ProgramInfo) -> Result<Self::Out> {
+    fn generate(&self, _: &mut dyn Write, info: &'i mut ProgramInfo) -> Result<Self::Out> {
         if self.is_global() {
             Ok(AsmValue::Global(info.value(*self)))
         } else {
             let fun = current_fun!(info);
-            let value = info.program().fun(fun.fun()).dfg().value(*self);
+            let value = info.program().func(fun.fun()).dfg().value(*self);
             Ok(match value.kind() {
                 ValueKind::Integer(i) => AsmValue::Const(i.value()),
                 ValueKind::FuncArgRef(i) => AsmValue::Arg(i.index()),
@@ -128,7 +174,7 @@ impl<'p, 'i> GenerateToAsm<'p, 'i> for Value {
 impl<'p, 'i> GenerateToAsm<'p, 'i> for ValueData {
     type Out = ();
 
-    fn generate(&self, f: &mut File, info: &mut ProgramInfo) -> Result<Self::Out> {
+    fn generate(&self, f: &mut dyn Write, info: &mut ProgramInfo) -> Result<Self::Out> {
         match self.kind() {
             ValueKind::Integer(v) => v.generate(f, info),
             ValueKind::ZeroInit(v) => v.generate(f, info, self),
@@ -151,7 +197,7 @@ impl<'p, 'i> GenerateToAsm<'p, 'i> for ValueData {
 impl<'p, 'i> GenerateToAsm<'p, 'i> for Integer {
     type Out = ();
 
-    fn generate(&self, f: &mut File, _: &mut ProgramInfo) -> Result<Self::Out> {
+    fn generate(&self, f: &mut dyn Write, _: &mut ProgramInfo) -> Result<Self::Out> {
         writeln!(f, "  .word {}", self.value())
     }
 }
@@ -159,7 +205,7 @@ impl<'p, 'i> GenerateToAsm<'p, 'i> for Integer {
 impl<'p, 'i> GenerateValueToAsm<'p, 'i> for ZeroInit {
     type Out = ();
 
-    fn generate(&self, f: &mut File, _: &mut ProgramInfo, v: &ValueData) -> Result<Self::Out> {
+    fn generate(&self, f: &mut dyn Write, _: &mut ProgramInfo, v: &ValueData) -> Result<Self::Out> {
         writeln!(f, "  .zero {}", v.ty().size())
     }
 }
@@ -167,7 +213,7 @@ impl<'p, 'i> GenerateValueToAsm<'p, 'i> for ZeroInit {
 impl<'p, 'i> GenerateToAsm<'p, 'i> for Aggregate {
     type Out = ();
 
-    fn generate(&self, f: &mut File, info: &mut ProgramInfo) -> Result<Self::Out> {
+    fn generate(&self, f: &mut dyn Write, info: &mut ProgramInfo) -> Result<Self::Out> {
         for &elem in self.elems() {
             info.program().borrow_value(elem).generate(f, info)?;
         }
@@ -178,7 +224,7 @@ impl<'p, 'i> GenerateToAsm<'p, 'i> for Aggregate {
 impl<'p, 'i> GenerateToAsm<'p, 'i> for GlobalAlloc {
     type Out = ();
 
-    fn generate(&self, f: &mut File, info: &mut ProgramInfo) -> Result<Self::Out> {
+    fn generate(&self, f: &mut dyn Write, info: &mut ProgramInfo) -> Result<Self::Out> {
         info.program().borrow_value(self.init()).generate(f, info)
     }
 }
@@ -186,7 +232,12 @@ impl<'p, 'i> GenerateToAsm<'p, 'i> for GlobalAlloc {
 impl<'p, 'i> GenerateValueToAsm<'p, 'i> for Load {
     type Out = ();
 
-    fn generate(&self, f: &mut File, info: &mut ProgramInfo, v: &ValueData) -> Result<Self::Out> {
+    fn generate(
+        &self,
+        f: &mut dyn Write,
+        info: &mut ProgramInfo,
+        v: &ValueData,
+    ) -> Result<Self::Out> {
         let src = self.src().generate(f, info)?;
         src.write_to(f, "t0")?;
         if src.is_ptr() {
@@ -199,7 +250,7 @@ impl<'p, 'i> GenerateValueToAsm<'p, 'i> for Load {
 impl<'p, 'i> GenerateToAsm<'p, 'i> for Store {
     type Out = ();
 
-    fn generate(&self, f: &mut File, info: &mut ProgramInfo) -> Result<Self::Out> {
+    fn generate(&self, f: &mut dyn Write, info: &mut ProgramInfo) -> Result<Self::Out> {
         let sp_offset = current_fun!(info).sp_offset();
         let value = self.value().generate(f, info)?;
         if matches!(value, AsmValue::Arg(_)) {
@@ -220,7 +271,12 @@ impl<'p, 'i> GenerateToAsm<'p, 'i> for Store {
 impl<'p, 'i> GenerateValueToAsm<'p, 'i> for GetPtr {
     type Out = ();
 
-    fn generate(&self, f: &mut File, info: &mut ProgramInfo, v: &ValueData) -> Result<Self::Out> {
+    fn generate(
+        &self,
+        f: &mut dyn Write,
+        info: &mut ProgramInfo,
+        v: &ValueData,
+    ) -> Result<Self::Out> {
         let src = self.src().generate(f, info)?;
         if src.is_ptr() {
             src.write_to(f, "t0")?;
@@ -242,7 +298,12 @@ impl<'p, 'i> GenerateValueToAsm<'p, 'i> for GetPtr {
 impl<'p, 'i> GenerateValueToAsm<'p, 'i> for GetElemPtr {
     type Out = ();
 
-    fn generate(&self, f: &mut File, info: &mut ProgramInfo, v: &ValueData) -> Result<Self::Out> {
+    fn generate(
+        &self,
+        f: &mut dyn Write,
+        info: &mut ProgramInfo,
+        v: &ValueData,
+    ) -> Result<Self::Out> {
         let src = self.src().generate(f, info)?;
         if src.is_ptr() {
             src.write_to(f, "t0")?;
@@ -264,7 +325,12 @@ impl<'p, 'i> GenerateValueToAsm<'p, 'i> for GetElemPtr {
 impl<'p, 'i> GenerateValueToAsm<'p, 'i> for Binary {
     type Out = ();
 
-    fn generate(&self, f: &mut File, info: &mut ProgramInfo, v: &ValueData) -> Result<Self::Out> {
+    fn generate(
+        &self,
+        f: &mut dyn Write,
+        info: &mut ProgramInfo,
+        v: &ValueData,
+    ) -> Result<Self::Out> {
         self.lhs().generate(f, info)?.write_to(f, "t0")?;
         self.rhs().generate(f, info)?.write_to(f, "t1")?;
         let mut builder = AsmBuilder::new(f, "t2");
@@ -306,7 +372,7 @@ impl<'p, 'i> GenerateValueToAsm<'p, 'i> for Binary {
 impl<'p, 'i> GenerateToAsm<'p, 'i> for Branch {
     type Out = ();
 
-    fn generate(&self, f: &mut File, info: &mut ProgramInfo) -> Result<Self::Out> {
+    fn generate(&self, f: &mut dyn Write, info: &mut ProgramInfo) -> Result<Self::Out> {
         self.cond().generate(f, info)?.write_to(f, "t0")?;
         let tlabel = self.true_bb().generate(f, info)?;
         AsmBuilder::new(f, "t1").bnez("t0", tlabel)?;
@@ -318,7 +384,7 @@ impl<'p, 'i> GenerateToAsm<'p, 'i> for Branch {
 impl<'p, 'i> GenerateToAsm<'p, 'i> for Jump {
     type Out = ();
 
-    fn generate(&self, f: &mut File, info: &mut ProgramInfo) -> Result<Self::Out> {
+    fn generate(&self, f: &mut dyn Write, info: &mut ProgramInfo) -> Result<Self::Out> {
         let label = self.target().generate(f, info)?;
         AsmBuilder::new(f, "t0").j(label)
     }
@@ -327,7 +393,15 @@ impl<'p, 'i> GenerateToAsm<'p, 'i> for Jump {
 impl<'p, 'i> GenerateValueToAsm<'p, 'i> for Call {
     type Out = ();
 
-    fn generate(&self, f: &mut File, info: &mut ProgramInfo, v: &ValueData) -> Result<Self::Out> {
+    fn generate(
+        &self,
+        f: &mut dyn Write,
+        info: &mut ProgramInfo,
+        v: &ValueData,
+    ) -> Result<Self::Out> {
+        if let Some(intrinsic) = intrinsic(info.program(), self) {
+            return generate_intrinsic(self, intrinsic, f, info, v);
+        }
         let args = self
             .args()
             .iter()
@@ -347,10 +421,81 @@ impl<'p, 'i> GenerateValueToAsm<'p, 'i> for Call {
     }
 }
 
+/// Returns the intrinsic called by the given call instruction.
+fn intrinsic(program: &Program, call: &Call) -> Option<Intrinsic> {
+    Intrinsic::from_name(&program.func(call.callee()).name()[1..])
+}
+
+/// Generates a call to an intrinsic as a single instruction.
+fn generate_intrinsic(
+    call: &Call,
+    intrinsic: Intrinsic,
+    f: &mut dyn Write,
+    info: &mut ProgramInfo,
+    v: &ValueData,
+) -> Result<()> {
+    let args = call.args();
+    match intrinsic {
+        Intrinsic::Lb | Intrinsic::Lbu | Intrinsic::Lh | Intrinsic::Lhu => {
+            let op = match intrinsic {
+                Intrinsic::Lb => "lb",
+                Intrinsic::Lbu => "lbu",
+                Intrinsic::Lh => "lh",
+                _ => "lhu",
+            };
+            write_ptr_to(f, info, args[0], "t0")?;
+            AsmBuilder::new(f, "t1").load(op, "t0", "t0", 0)?;
+        }
+        Intrinsic::Sb | Intrinsic::Sh => {
+            let op = if intrinsic == Intrinsic::Sb {
+                "sb"
+            } else {
+                "sh"
+            };
+            args[0].generate(f, info)?.write_to(f, "t0")?;
+            write_ptr_to(f, info, args[1], "t1")?;
+            return AsmBuilder::new(f, "t2").store(op, "t0", "t1", 0);
+        }
+        Intrinsic::Divu | Intrinsic::Remu | Intrinsic::Mulhu => {
+            let op = match intrinsic {
+                Intrinsic::Divu => "divu",
+                Intrinsic::Remu => "remu",
+                _ => "mulhu",
+            };
+            args[0].generate(f, info)?.write_to(f, "t0")?;
+            args[1].generate(f, info)?.write_to(f, "t1")?;
+            AsmBuilder::new(f, "t2").op2(op, "t0", "t0", "t1")?;
+        }
+        Intrinsic::Offset => {
+            write_ptr_to(f, info, args[0], "t0")?;
+            args[1].generate(f, info)?.write_to(f, "t1")?;
+            AsmBuilder::new(f, "t2").op2("add", "t0", "t0", "t1")?;
+        }
+        Intrinsic::Cast(_) | Intrinsic::Addr(_) => write_ptr_to(f, info, args[0], "t0")?,
+    }
+    asm_value!(info, v).read_from(f, "t0", "t1")
+}
+
+/// Writes the pointer argument of an intrinsic to the given register,
+/// variables are passed by their addresses.
+fn write_ptr_to(
+    f: &mut dyn Write,
+    info: &mut ProgramInfo,
+    ptr: Value,
+    reg: &'static str,
+) -> Result<()> {
+    let ptr = ptr.generate(f, info)?;
+    if ptr.is_ptr() {
+        ptr.write_to(f, reg)
+    } else {
+        ptr.write_addr_to(f, reg)
+    }
+}
+
 impl<'p, 'i> GenerateToAsm<'p, 'i> for Return {
     type Out = ();
 
-    fn generate(&self, f: &mut File, info: &mut ProgramInfo) -> Result<Self::Out> {
+    fn generate(&self, f: &mut dyn Write, info: &mut ProgramInfo) -> Result<Self::Out> {
         if let Some(value) = self.value() {
             value.generate(f, info)?.write_to(f, "a0")?;
         }
@@ -1,11 +1,12 @@
-use super::fun::FunctionInfo;
+use super::func::FunctionInfo;
 use koopa::ir::{Program, Value};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Some necessary information during assembly generation.
 pub struct ProgramInfo<'p> {
     program: &'p Program,
     values: HashMap<Value, String>,
+    names: HashSet<String>,
     current_fun: Option<FunctionInfo>,
 }
 
@@ -28,9 +29,16 @@ pub(crate) use current_fun_mut;
 impl<'p> ProgramInfo<'p> {
     /// Creates a new program information.
     pub fn new(program: &'p Program) -> Self {
+        // functions keep their names, they are called from outside
+        let names = program
+            .funcs()
+            .values()
+            .map(|fun| fun.name()[1..].to_string())
+            .collect();
         Self {
             program,
             values: HashMap::new(),
+            names,
             current_fun: None,
         }
     }
@@ -45,9 +53,19 @@ impl<'p> ProgramInfo<'p> {
         self.values.get(&value).unwrap()
     }
 
-    /// Inserts a new global value name.
-    pub fn insert_value(&mut self, value: Value, name: String) {
-        self.values.insert(value, name);
+    /// Inserts a new global value name, which is made unique the same way
+    /// Koopa does when dumping the program. Returns the inserted name.
+    pub fn insert_value(&mut self, value: Value, name: &str) -> &str {
+        let name = if self.names.contains(name) {
+            (0..)
+                .map(|id| format!("{}_{}", name, id))
+                .find(|name| !self.names.contains(name))
+                .unwrap()
+        } else {
+            name.to_string()
+        };
+        self.names.insert(name.clone());
+        self.values.entry(value).or_insert(name)
     }
 
     /// Returns a reference to the current funtion information.
@@ -1,17 +1,17 @@
 mod builder;
-mod fun;
+mod func;
 mod gen;
 mod info;
+mod table;
 mod values;
 
 use gen::GenerateToAsm;
 use info::ProgramInfo;
 use koopa::ir::{Program, Type};
-use std::fs::File;
-use std::io::Result;
+use std::io::{Result, Write};
 
 /// Generates the given Koopa IR program to RISC-V assembly.
-pub fn generate_asm(program: &Program, path: &str) -> Result<()> {
+pub fn generate_asm(program: &Program, f: &mut dyn Write) -> Result<()> {
     Type::set_ptr_size(4);
-    program.generate(&mut File::create(path)?, &mut ProgramInfo::new(program))
+    program.generate(f, &mut ProgramInfo::new(program))
 }
@@ -0,0 +1,143 @@
+use koopa::ir::values::BinaryOp;
+use koopa::ir::{BasicBlock, FunctionData, Value, ValueKind};
+use std::collections::HashSet;
+
+/// Minimum number of tests in a chain lowered to a jump table.
+const MIN_TESTS: usize = 4;
+
+/// Maximum number of entries in a jump table.
+const MAX_ENTRIES: i64 = 1024;
+
+/// A jump table replacing a chain of basic blocks that compare
+/// the same value with constants.
+#[derive(Clone)]
+pub struct JumpTable {
+    /// Label of the table data.
+    pub label: String,
+    /// The compared value.
+    pub value: Value,
+    /// The value of the first entry.
+    pub min: i32,
+    /// Targets of entries, values that match no test jump to the default target.
+    pub targets: Vec<BasicBlock>,
+    pub default: BasicBlock,
+}
+
+/// A basic block that only tests if the value is in a range,
+/// and branches to `target` or to the next test.
+struct Test {
+    value: Value,
+    lo: i32,
+    hi: i32,
+    target: BasicBlock,
+    next: BasicBlock,
+}
+
+/// Finds chains of tests that are dense enough to be lowered to jump tables.
+/// Returns the first basic block of each chain and the table (with an empty label).
+pub fn find_jump_tables(fun: &FunctionData) -> Vec<(BasicBlock, JumpTable)> {
+    let mut tables = Vec::new();
+    let mut visited = HashSet::new();
+    for &bb in fun.layout().bbs().keys() {
+        if !visited.insert(bb) {
+            continue;
+        }
+        let Some(head) = test(fun, bb) else {
+            continue;
+        };
+        // collect the chain, earlier tests take precedence
+        let value = head.value;
+        let mut tests = vec![head];
+        while let Some(next) = test(fun, tests.last().unwrap().next) {
+            if next.value != value || !visited.insert(tests.last().unwrap().next) {
+                break;
+            }
+            tests.push(next);
+        }
+        if let Some(table) = build_table(&tests) {
+            tables.push((bb, table));
+        }
+    }
+    tables
+}
+
+/// Builds a jump table from the given chain, returns `None` if
+/// the chain is too short or too sparse.
+fn build_table(tests: &[Test]) -> Option<JumpTable> {
+    if tests.len() < MIN_TESTS {
+        return None;
+    }
+    let min = tests.iter().map(|t| t.lo).min()?;
+    let max = tests.iter().map(|t| t.hi).max()?;
+    let len = max as i64 - min as i64 + 1;
+    if len > MAX_ENTRIES {
+        return None;
+    }
+    let mut entries = vec![None; len as usize];
+    for t in tests {
+        for entry in &mut entries[(t.lo - min) as usize..=(t.hi - min) as usize] {
+            entry.get_or_insert(t.target);
+        }
+    }
+    // at least half of the entries must not be the default target
+    if entries.iter().filter(|e| e.is_some()).count() * 2 < entries.len() {
+        return None;
+    }
+    let default = tests.last().unwrap().next;
+    Some(JumpTable {
+        label: String::new(),
+        value: tests[0].value,
+        min,
+        targets: entries.into_iter().map(|e| e.unwrap_or(default)).collect(),
+        default,
+    })
+}
+
+/// Returns the test if the given basic block only contains
+/// `eq %v, k` or `ge %v, lo`, `le %v, hi`, `and`, followed by a branch.
+fn test(fun: &FunctionData, bb: BasicBlock) -> Option<Test> {
+    let insts: Vec<_> = fun
+        .layout()
+        .bbs()
+        .node(&bb)?
+        .insts()
+        .keys()
+        .copied()
+        .collect();
+    let kind = |v: Value| fun.dfg().value(v).kind();
+    let integer = |v: Value| match kind(v) {
+        ValueKind::Integer(i) => Some(i.value()),
+        _ => None,
+    };
+    let compare = |v: Value, op: BinaryOp| match kind(v) {
+        ValueKind::Binary(b) if b.op() == op => Some((b.lhs(), integer(b.rhs())?)),
+        _ => None,
+    };
+    let (value, lo, hi, cond) = match insts[..] {
+        [eq, _] => {
+            let (value, k) = compare(eq, BinaryOp::Eq)?;
+            (value, k, k, eq)
+        }
+        [ge, le, and, _] => {
+            let (value, lo) = compare(ge, BinaryOp::Ge)?;
+            let (hi_value, hi) = compare(le, BinaryOp::Le)?;
+            let is_and = matches!(kind(and), ValueKind::Binary(b)
+                if b.op() == BinaryOp::And && b.lhs() == ge && b.rhs() == le);
+            if hi_value != value || !is_and {
+                return None;
+            }
+            (value, lo, hi, and)
+        }
+        _ => return None,
+    };
+    match kind(*insts.last()?) {
+        ValueKind::Branch(br) if br.cond() == cond && lo <= hi => Some(Test {
+            value,
+            lo,
+            hi,
+            target: br.true_bb(),
+            next: br.false_bb(),
+        }),
+        _ => None,
+    }
+}
@@ -1,7 +1,6 @@
 use super::builder::AsmBuilder;
-use super::fun::Slot;
-use std::fs::File;
-use std::io::Result;
+use super::func::Slot;
+use std::io::{Result, Write};
 
 /// A global/local value.
 pub enum AsmValue<'i> {
@@ -27,7 +26,7 @@ impl<'i> AsmValue<'i> {
     }
 
     /// Writes the assembly value to the given register.
-    pub fn write_to(&self, f: &mut File, reg: &'static str) -> Result<()> {
+    pub fn write_to(&self, f: &mut dyn Write, reg: &'static str) -> Result<()> {
         let mut builder = AsmBuilder::new(f, reg);
         match self {
             Self::Global(symbol) => {
@@ -41,7 +40,7 @@ impl<'i> AsmValue<'i> {
     }
 
     /// Writes the address of assembly value to the give register.
-    pub fn write_addr_to(&self, f: &mut File, reg: &'static str) -> Result<()> {
+    pub fn write_addr_to(&self, f: &mut dyn Write, reg: &'static str) -> Result<()> {
         let mut builder = AsmBuilder::new(f, reg);
         match self {
             Self::Global(symbol) => builder.la(reg, symbol),
@@ -51,7 +50,12 @@ impl<'i> AsmValue<'i> {
     }
 
     /// Writes the assembly value (argument) to the given register.
-    pub fn write_arg_to(&self, f: &mut File, reg: &'static str, sp_offset: usize) -> Result<()> {
+    pub fn write_arg_to(
+        &self,
+        f: &mut dyn Write,
+        reg: &'static str,
+        sp_offset: usize,
+    ) -> Result<()> {
         let mut builder = AsmBuilder::new(f, reg);
         match self {
             Self::Arg(index) => {
@@ -66,7 +70,12 @@ impl<'i> AsmValue<'i> {
     }
 
     /// Reads the value of the given register to the assembly value.
-    pub fn read_from(&self, f: &mut File, reg: &'static str, temp: &'static str) -> Result<()> {
+    pub fn read_from(
+        &self,
+        f: &mut dyn Write,
+        reg: &'static str,
+        temp: &'static str,
+    ) -> Result<()> {
         let mut builder = AsmBuilder::new(f, temp);
         match self {
             Self::Global(symbol) => {
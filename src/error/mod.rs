@@ -1,18 +1,11 @@
 use codespan_reporting::diagnostic::Label;
 
-mod reporter;
-
-pub use reporter::Reporter;
+/// Identifier of a source file in a [`Session`](crate::session::Session).
+pub type FileId = usize;
 
 pub struct Error {
     pub message: String,
     pub code: String,
-    pub labels: Vec<Label<usize>>,
+    pub labels: Vec<Label<FileId>>,
     pub note: Vec<String>,
 }
-
-impl Reporter {
-    pub fn reporter_id(reporter: Option<Reporter>) -> u32 {
-        unsafe { reporter.unwrap().id }
-    }
-}
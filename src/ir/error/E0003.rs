@@ -1,28 +0,0 @@
-use crate::ast;
-use crate::error::{Error, Reporter};
-use crate::ir::scopes::Scopes;
-use codespan_reporting::diagnostic::Label;
-
-use super::REPORTER;
-
-/// E0003: Break statement is not in a loop
-pub struct E0003<'ast> {
-    pub ast: &'ast ast::Break,
-}
-
-impl<'ast> E0003<'ast> {
-    pub fn run<Out>(&self, scopes: &mut Scopes<'ast>) -> Result<Out, Error> {
-        scopes.loop_info.last().ok_or(Err(Error {
-            message: String::from("The `break` statement is not in the loop"),
-            code: String::from("E0003"),
-            labels: vec![Label::primary(
-                unsafe { Reporter::reporter_id(REPORTER) },
-                self.ast.pos.0..self.ast.pos.1,
-            )
-            .with_message("This is not within a loop statement".to_string())],
-            note: vec![String::from(
-                "`break` must be within a loop statement",
-            )],
-        }))?
-    }
-}
@@ -9,17 +9,25 @@ pub struct FunctionInfo {
     end: BasicBlock,
     cur: BasicBlock,
     ret_val: Option<Value>,
+    ret_ptr: Option<Value>,
 }
 
 impl FunctionInfo {
     /// Creates a new funtion information.
-    pub fn new(fun: Function, entry: BasicBlock, end: BasicBlock, ret_val: Option<Value>) -> Self {
+    pub fn new(
+        fun: Function,
+        entry: BasicBlock,
+        end: BasicBlock,
+        ret_val: Option<Value>,
+        ret_ptr: Option<Value>,
+    ) -> Self {
         Self {
             fun,
             entry,
             end,
             cur: entry,
             ret_val,
+            ret_ptr,
         }
     }
 
@@ -38,6 +46,12 @@ impl FunctionInfo {
         self.ret_val
     }
 
+    /// Returns the variable holding the pointer the `I64` return value
+    /// is stored to, which is passed as the first parameter.
+    pub fn ret_ptr(&self) -> Option<Value> {
+        self.ret_ptr
+    }
+
     /// Creates a new basic block in funtion.
     pub fn new_basic_block(&self, program: &mut Program, name: Option<&str>) -> BasicBlock {
         program
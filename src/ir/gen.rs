@@ -1,44 +1,32 @@
-use super::error::E0003;
-use super::error::E0004;
-use super::error::E0005;
-use super::error::E0006;
-use super::error::E0007;
-use super::error::E0008;
-use super::error::E0009;
-use super::error::E0010;
-use super::error::Result;
-use super::error::E0002;
-use super::eval::Evaluate;
 use super::fun::FunctionInfo;
-use super::scopes::{current_fun, current_fun_mut, Scopes};
-use super::values::{ExprValue, Initializer, Value};
+use super::intrinsic::Intrinsic;
+use super::scopes::{current_fun, current_fun_mut, LoopInfo, Scopes};
+use super::values::{self, call_intrinsic, ExprValue, Initializer, Value};
 use super::DimsToType;
 use crate::ast::{self, *};
+use crate::sema::Ty;
 use koopa::ir::builder_traits::*;
-use koopa::ir::{BinaryOp, FunctionData, Program, Type, TypeKind};
+use koopa::ir::Value as IrValue;
+use koopa::ir::{BasicBlock, BinaryOp, FunctionData, Program, Type, TypeKind, ValueKind};
 
 /// Trait for generating Koopa IR program.
 pub trait GenerateProgram<'ast> {
     type Out;
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out>;
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out;
 }
 
 impl<'ast> GenerateProgram<'ast> for CompUnit {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         let mut new_decl = |name, params_ty, ret_ty| {
-            scopes
-                .new_fun(
-                    name,
-                    program.new_func(FunctionData::new_decl(
-                        format!("@{}", name),
-                        params_ty,
-                        ret_ty,
-                    )),
-                )
-                .unwrap();
+            let fun = program.new_func(FunctionData::new_decl(
+                format!("@{}", name),
+                params_ty,
+                ret_ty,
+            ));
+            scopes.new_fun(name, fun);
         };
         // generate SysY library funtion declarations
         new_decl("getint", vec![], Type::get_i32());
@@ -55,23 +43,43 @@ impl<'ast> GenerateProgram<'ast> for CompUnit {
             vec![Type::get_i32(), Type::get_pointer(Type::get_i32())],
             Type::get_unit(),
         );
+        new_decl(
+            "putstr",
+            vec![Type::get_pointer(Type::get_i32())],
+            Type::get_unit(),
+        );
         new_decl("starttime", vec![], Type::get_unit());
         new_decl("stoptime", vec![], Type::get_unit());
+        // declare intrinsics, the unused ones are removed after generation
+        for intrinsic in Intrinsic::ALL {
+            declare_intrinsic(program, scopes, intrinsic);
+        }
         // generate global items
         for item in &self.items {
-            item.gen(program, scopes)?;
+            item.gen(program, scopes);
         }
-        Ok(())
     }
 }
 
+/// Declares the given intrinsic.
+fn declare_intrinsic(program: &mut Program, scopes: &mut Scopes, intrinsic: Intrinsic) {
+    let fun = program.new_func(FunctionData::new_decl(
+        format!("@{}", intrinsic.name()),
+        intrinsic.params_ty(),
+        intrinsic.ret_ty(),
+    ));
+    scopes.intrinsics.insert(intrinsic, fun);
+}
+
 impl<'ast> GenerateProgram<'ast> for GlobalItem {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         match self {
             Self::Decl(decl) => decl.gen(program, scopes),
             Self::FuncDef(def) => def.gen(program, scopes),
+            // the layouts of structs are computed by the static checks
+            Self::Struct(_) | Self::Error(_) => {}
         }
     }
 }
@@ -79,7 +87,7 @@ impl<'ast> GenerateProgram<'ast> for GlobalItem {
 impl<'ast> GenerateProgram<'ast> for Decl {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         match self {
             Self::Const(c) => c.gen(program, scopes),
             Self::Var(v) => v.gen(program, scopes),
@@ -90,30 +98,29 @@ impl<'ast> GenerateProgram<'ast> for Decl {
 impl<'ast> GenerateProgram<'ast> for ConstDecl {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         for def in &self.defs {
-            def.gen(program, scopes)?;
+            def.gen(program, scopes);
         }
-        Ok(())
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for ConstDef {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         // generate type and initializer
-        let ty = self.dims.to_type(scopes)?;
-        let init = self.init.gen(program, scopes)?.reshape(&ty)?;
+        let ty = self.dims.to_type(scopes);
+        let init = self.init.gen(program, scopes).reshape(&ty);
         // generate constant
         if ty.is_i32() {
             match init {
-                Initializer::Const(num) => scopes.new_value(&self.id.name, Value::Const(num))?,
+                Initializer::Const(num) => scopes.new_value(&self.id.name, Value::Const(num)),
                 _ => unreachable!(),
             }
         } else {
             let value = if scopes.is_global() {
-                let init = init.into_const(program, scopes)?;
+                let init = init.into_const(program, scopes);
                 let value = program.new_value().global_alloc(init);
                 program.set_value_name(value, Some(format!("@{}", self.id.name)));
                 value
@@ -124,53 +131,61 @@ impl<'ast> GenerateProgram<'ast> for ConstDef {
                 alloc
             };
             // add to scope
-            scopes.new_value(&self.id.name, Value::Value(value))?;
+            scopes.new_value(&self.id.name, Value::Value(value));
         }
-        Ok(())
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for ConstInitVal {
     type Out = Initializer;
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
-        Ok(match self {
-            Self::Expr(exp) => Initializer::Const(exp.gen(program, scopes)?),
-            Self::List(list) => Initializer::List(
-                list.iter()
-                    .map(|v| v.gen(program, scopes))
-                    .collect::<Result<_>>()?,
-            ),
-        })
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        match self {
+            Self::Expr(exp) => Initializer::Const(exp.gen(program, scopes)),
+            Self::List(list) => {
+                Initializer::List(list.iter().map(|v| v.gen(program, scopes)).collect())
+            }
+        }
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for VarDecl {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         for def in &self.defs {
-            def.gen(program, scopes)?;
+            def.gen(program, scopes);
         }
-        Ok(())
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for VarDef {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
-        // generate type and initializer
-        let ty = self.dims.to_type(scopes)?;
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        let types = scopes.types;
+        let struct_name = match types.ty(self).elem().0 {
+            ty @ (Ty::Bool | Ty::Pointer(_)) => return self.gen_scalar(program, scopes, ty),
+            Ty::Sized(ty) => return self.gen_sized(program, scopes, *ty),
+            Ty::Struct(name) => Some(*name),
+            _ => None,
+        };
+        // generate type and initializer, structs are arrays of words
+        let ty = match struct_name {
+            Some(name) => {
+                let base = scopes.struct_info(name).ty();
+                self.dims.to_array(base, scopes)
+            }
+            None => self.dims.to_type(scopes),
+        };
         let init = self
             .init
             .as_ref()
-            .map(|i| i.gen(program, scopes)?.reshape(&ty))
-            .transpose()?;
+            .map(|i| i.gen(program, scopes).reshape(&ty));
         // generate variable
         let value = if scopes.is_global() {
             let init = match init {
-                Some(init) => init.into_const(program, scopes)?,
+                Some(init) => init.into_const(program, scopes),
                 None => program.new_value().zero_init(ty),
             };
             let value = program.new_value().global_alloc(init);
@@ -185,123 +200,314 @@ impl<'ast> GenerateProgram<'ast> for VarDef {
             alloc
         };
         // add to scope
-        scopes.new_value(&self.id.name, Value::Value(value))?;
-        Ok(())
+        let value = match struct_name {
+            Some(name) => Value::Struct(value, name),
+            None => Value::Value(value),
+        };
+        scopes.new_value(&self.id.name, value);
+    }
+}
+
+impl<'ast> VarDef {
+    /// Generates a Bool or pointer variable, which is initialized by an expression,
+    /// or an array of pointers, which is not initialized. The elements of
+    /// arrays of pointers are stored in an array of one dimension.
+    fn gen_scalar(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>, ty: &Ty<'ast>) {
+        let lens = self.dims.to_lens(scopes);
+        let ir_ty = match ty {
+            Ty::Pointer(base) if lens.is_empty() => base.pointer_to(),
+            Ty::Pointer(base) => Type::get_array(base.pointer_to(), lens.iter().product()),
+            _ => Type::get_i32(),
+        };
+        // generate variable, global Bools are initialized by constant expressions
+        // and global pointers are never initialized, as no constant is a pointer
+        let value = if scopes.is_global() {
+            let init = match &self.init {
+                Some(InitVal::Expr(exp)) => program.new_value().integer(scopes.types.value(exp)),
+                Some(InitVal::List(_)) => unreachable!(),
+                None => program.new_value().zero_init(ir_ty),
+            };
+            let value = program.new_value().global_alloc(init);
+            program.set_value_name(value, Some(format!("@{}", self.id.name)));
+            value
+        } else {
+            let init = match &self.init {
+                Some(InitVal::Expr(exp)) => {
+                    Some(exp.gen(program, scopes).into_val(program, scopes))
+                }
+                Some(InitVal::List(_)) => unreachable!(),
+                None => None,
+            };
+            let info = current_fun!(scopes);
+            let alloc = info.new_alloc(program, ir_ty, Some(&self.id.name));
+            if let Some(init) = init {
+                let store = info.new_value(program).store(init, alloc);
+                info.push_inst(program, store);
+            }
+            alloc
+        };
+        // add to scope
+        let value = match ty {
+            Ty::Pointer(base) => Value::Pointer(value, (**base).clone(), lens),
+            _ => Value::Bool(value),
+        };
+        scopes.new_value(&self.id.name, value);
+    }
+}
+
+impl<'ast> VarDef {
+    /// Generates a fixed-width integer variable or array,
+    /// arrays are packed into words.
+    fn gen_sized(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>, ty: IntTy) {
+        let lens = self.dims.to_lens(scopes);
+        let len = lens.iter().product::<usize>();
+        // `I64` variables are arrays of two words
+        let is_word = lens.is_empty() && ty.size() <= 4;
+        let ir_ty = match is_word {
+            true => Type::get_i32(),
+            false => Type::get_array(Type::get_i32(), (len * ty.size()).div_ceil(4)),
+        };
+        // generate initializer elements, reshaped by the shape of the array
+        let elems = self.init.as_ref().map(|init| {
+            init.gen(program, scopes)
+                .reshape(&self.dims.to_type(scopes))
+                .into_elems()
+        });
+        // generate variable
+        let value = if scopes.is_global() {
+            let init = match elems {
+                Some(elems) => {
+                    let elems: Vec<_> = elems
+                        .into_iter()
+                        .map(|elem| match elem {
+                            Initializer::Const(num) => num,
+                            _ => unreachable!(),
+                        })
+                        .collect();
+                    let mut words = pack(&elems, ty)
+                        .into_iter()
+                        .map(|word| program.new_value().integer(word))
+                        .collect::<Vec<_>>();
+                    match is_word {
+                        true => words.pop().unwrap(),
+                        false => program.new_value().aggregate(words),
+                    }
+                }
+                None => program.new_value().zero_init(ir_ty),
+            };
+            let value = program.new_value().global_alloc(init);
+            program.set_value_name(value, Some(format!("@{}", self.id.name)));
+            value
+        } else {
+            let info = current_fun!(scopes);
+            let alloc = info.new_alloc(program, ir_ty, Some(&self.id.name));
+            if let Some(elems) = elems {
+                let base = match is_word {
+                    true => alloc,
+                    false => values::long_ptr(program, scopes, alloc),
+                };
+                for (i, elem) in elems.into_iter().enumerate() {
+                    let info = current_fun!(scopes);
+                    let value = match elem {
+                        Initializer::Const(num) => info.new_value(program).integer(num),
+                        Initializer::Value(value) => value,
+                        Initializer::List(_) => unreachable!(),
+                    };
+                    let ptr = match i {
+                        0 => base,
+                        _ => {
+                            let index = info.new_value(program).integer(i as i32);
+                            let ty = Ty::Sized(ty);
+                            generate_offset(program, scopes, base, &ty, BinaryOp::Add, index)
+                        }
+                    };
+                    values::store(program, scopes, value, ptr, &Ty::Sized(ty));
+                }
+            }
+            alloc
+        };
+        // add to scope
+        scopes.new_value(&self.id.name, Value::Sized(value, ty, lens));
     }
 }
 
+/// Packs the elements of a fixed-width integer array into little-endian words,
+/// `I64` elements are sign-extended.
+fn pack(elems: &[i32], ty: IntTy) -> Vec<i32> {
+    let bytes: Vec<u8> = elems
+        .iter()
+        .flat_map(|&elem| (elem as i64).to_le_bytes().into_iter().take(ty.size()))
+        .collect();
+    bytes
+        .chunks(4)
+        .map(|chunk| {
+            let mut word = [0; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            i32::from_le_bytes(word)
+        })
+        .collect()
+}
+
 impl<'ast> GenerateProgram<'ast> for InitVal {
     type Out = Initializer;
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
-        Ok(match self {
-            // self.ast.eval(scopes)
-            Self::Expr(exp) => (E0002::E0002 { ast: exp }).run(program, scopes),
-            Self::List(list) => Initializer::List(
-                list.iter()
-                    .map(|v| v.gen(program, scopes))
-                    .collect::<Result<_>>()?,
-            ),
-        })
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        match self {
+            // global values are initialized by constant expressions
+            Self::Expr(exp) if scopes.is_global() => Initializer::Const(scopes.types.value(exp)),
+            Self::Expr(exp) => {
+                Initializer::Value(exp.gen(program, scopes).into_val(program, scopes))
+            }
+            Self::List(list) => {
+                Initializer::List(list.iter().map(|v| v.gen(program, scopes)).collect())
+            }
+        }
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for FuncDef {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
-        // generate parameter types and return type
-        let params_ty = self
-            .params
-            .iter()
-            .map(|p| p.gen(program, scopes))
-            .collect::<Result<Vec<_>>>()?;
-        let ret_ty = self.ty.gen(program, scopes)?;
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        // generate parameter types and return type, `I64` return values
+        // are stored to a pointer passed as the first parameter
+        let mut params_ty: Vec<_> = self.params.iter().map(|p| p.gen(program, scopes)).collect();
+        let ret_ty = self.ty.gen(program, scopes);
+        let is_long = matches!(self.ty, ast::Type::Sized(IntTy::I64, _));
+        if is_long {
+            params_ty.insert(0, Ty::Sized(IntTy::I64).pointer_to());
+        }
         // create new fucntion
-        let mut data = FunctionData::new(format!("@{}", self.id.name), params_ty, ret_ty);
+        let mut data = FunctionData::new(format!("@{}", self.id.name), params_ty, ret_ty.clone());
         // get parameter list
-        let params = data.params().to_owned();
+        let mut params = data.params().to_owned();
+        let ret_arg = is_long.then(|| params.remove(0));
         // generate entry/end/cur block
         let entry = data.dfg_mut().new_bb().basic_block(Some("%entry".into()));
         let end = data.dfg_mut().new_bb().basic_block(Some("%end".into()));
         let cur = data.dfg_mut().new_bb().basic_block(None);
         let mut ret_val = None;
         // generate return value
-        if matches!(self.ty, ast::Type::Int(_)) {
-            let alloc = data.dfg_mut().new_value().alloc(Type::get_i32());
+        if !ret_ty.is_unit() {
+            let alloc = data.dfg_mut().new_value().alloc(ret_ty);
             data.dfg_mut().set_value_name(alloc, Some("%ret".into()));
             ret_val = Some(alloc);
         }
+        let ret_ptr = ret_arg.map(|_| {
+            let ty = Ty::Sized(IntTy::I64).pointer_to();
+            let alloc = data.dfg_mut().new_value().alloc(ty);
+            data.dfg_mut().set_value_name(alloc, Some("%ret".into()));
+            alloc
+        });
         // update funtion information
         let fun = program.new_func(data);
-        let mut info = FunctionInfo::new(fun, entry, end, ret_val);
+        let mut info = FunctionInfo::new(fun, entry, end, ret_val, ret_ptr);
         info.push_bb(program, entry);
-        if let Some(ret_val) = info.ret_val() {
-            info.push_inst(program, ret_val);
+        if let Some(alloc) = info.ret_val().or(ret_ptr) {
+            info.push_inst(program, alloc);
         }
         info.push_bb(program, cur);
+        if let (Some(ret_ptr), Some(ret_arg)) = (ret_ptr, ret_arg) {
+            let store = info.new_value(program).store(ret_arg, ret_ptr);
+            info.push_inst(program, store);
+        }
+        scopes.current_fun = Some(info);
         // generate allocations for parameters
         scopes.enter();
+        let types = scopes.types;
         for (param, value) in self.params.iter().zip(params) {
+            let info = current_fun!(scopes);
             let ty = program.func(fun).dfg().value(value).ty().clone();
-            let alloc = info.new_alloc(program, ty, Some(&param.id.name));
+            let mut alloc = info.new_alloc(program, ty, Some(&param.id.name));
             let store = info.new_value(program).store(value, alloc);
             info.push_inst(program, store);
-            scopes.new_value(&param.id.name, Value::Value(alloc))?;
+            // `I64` arguments are pointers to their words, which are copied
+            if let ty @ Ty::Sized(IntTy::I64) = types.ty(param) {
+                let ptr = info.new_value(program).load(alloc);
+                info.push_inst(program, ptr);
+                let ir_ty = Type::get_array(Type::get_i32(), 2);
+                alloc = info.new_alloc(program, ir_ty, None);
+                let words = values::long_ptr(program, scopes, alloc);
+                values::store(program, scopes, ptr, words, ty);
+            }
+            let value = match types.ty(param) {
+                Ty::Bool => Value::Bool(alloc),
+                Ty::Sized(ty) => Value::Sized(alloc, *ty, Vec::new()),
+                Ty::Struct(name) => Value::Struct(alloc, name),
+                Ty::Pointer(ty) => Value::Pointer(alloc, (**ty).clone(), Vec::new()),
+                _ => Value::Value(alloc),
+            };
+            scopes.new_value(&param.id.name, value);
         }
         // update scope
-        scopes.new_fun(&self.id.name, fun)?;
-        scopes.current_fun = Some(info);
+        scopes.new_fun(&self.id.name, fun);
         // generate funtion body
-        self.block.gen(program, scopes)?;
+        self.block.gen(program, scopes);
         scopes.exit();
         // handle end basic block
         let mut info = scopes.current_fun.take().unwrap();
         info.seal_entry(program, cur);
         info.seal_fun(program);
-        Ok(())
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for ast::Type {
     type Out = Type;
 
-    fn gen(&'ast self, _: &mut Program, _: &mut Scopes<'ast>) -> Result<Self::Out> {
-        Ok(match self {
-            Self::Unit(_) => Type::get_unit(),
-            Self::Int(_) => Type::get_i32(),
-        })
+    fn gen(&'ast self, _: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        match self {
+            // `I64` return values are stored to a pointer passed by the caller
+            Self::Unit(_) | Self::Sized(IntTy::I64, _) => Type::get_unit(),
+            Self::Int(_) | Self::Bool(_) | Self::Sized(..) => Type::get_i32(),
+            Self::Pointer(..) => match scopes.types.ty(self) {
+                Ty::Pointer(base) => base.pointer_to(),
+                _ => unreachable!(),
+            },
+            Self::Struct(_) => unreachable!(),
+        }
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for FuncFParam {
     type Out = Type;
 
-    fn gen(&'ast self, _: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
-        Ok(match &self.dims {
-            Some(dims) => Type::get_pointer(dims.to_type(scopes)?),
-            None => Type::get_i32(),
-        })
+    fn gen(&'ast self, _: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        match scopes.types.ty(self) {
+            // structs are passed by a pointer to their first word,
+            // and `I64` values by a pointer to their low word
+            ty @ (Ty::Struct(_) | Ty::Sized(IntTy::I64)) => ty.pointer_to(),
+            Ty::Pointer(base) => base.pointer_to(),
+            // arrays are passed as pointers to their first element
+            Ty::Array(elem, _) => Type::get_pointer(array_ty(elem)),
+            _ => Type::get_i32(),
+        }
+    }
+}
+
+/// Returns the Koopa IR type of an integer or an integer array.
+fn array_ty(ty: &Ty) -> Type {
+    match ty {
+        Ty::Array(elem, Some(len)) => Type::get_array(array_ty(elem), *len),
+        _ => Type::get_i32(),
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for Block {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         scopes.enter();
         for item in &self.items {
-            item.gen(program, scopes)?;
+            item.gen(program, scopes);
         }
         scopes.exit();
-        Ok(())
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for BlockItem {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         match self {
             Self::Decl(decl) => decl.gen(program, scopes),
             Self::Stmt(stmt) => stmt.gen(program, scopes),
@@ -312,16 +518,24 @@ impl<'ast> GenerateProgram<'ast> for BlockItem {
 impl<'ast> GenerateProgram<'ast> for Stmt {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         match self {
             Self::Assign(s) => s.gen(program, scopes),
+            Self::CompoundAssign(s) => s.gen(program, scopes),
+            Self::Increment(s) => s.gen(program, scopes),
             Self::ExprStmt(s) => s.gen(program, scopes),
             Self::Block(s) => s.gen(program, scopes),
             Self::If(s) => s.gen(program, scopes),
             Self::While(s) => s.gen(program, scopes),
+            Self::Loop(s) => s.gen(program, scopes),
+            Self::DoWhile(s) => s.gen(program, scopes),
+            Self::Match(s) => s.gen(program, scopes),
+            Self::For(s) => s.gen(program, scopes),
+            Self::ForRange(s) => s.gen(program, scopes),
             Self::Break(s) => s.gen(program, scopes),
             Self::Continue(s) => s.gen(program, scopes),
             Self::Return(s) => s.gen(program, scopes),
+            Self::Error(_) => {}
         }
     }
 }
@@ -329,35 +543,119 @@ impl<'ast> GenerateProgram<'ast> for Stmt {
 impl<'ast> GenerateProgram<'ast> for Assign {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         // generate value and left-value pointer
-        let exp = self.exp.gen(program, scopes)?.into_int(program, scopes)?;
-        let lval = self.lval.gen(program, scopes)?.into_ptr()?;
+        let exp = self.exp.gen(program, scopes).into_rvalue(program, scopes);
+        let lval = self.lval.gen(program, scopes).into_ptr();
+        let exp = exp.into_val(program, scopes);
         // generate store
-        let info = current_fun!(scopes);
-        let store = info.new_value(program).store(exp, lval);
-        info.push_inst(program, store);
-        Ok(())
+        let ty = scopes.types.ty(&self.lval);
+        values::store(program, scopes, exp, lval, ty);
+    }
+}
+
+impl<'ast> GenerateProgram<'ast> for CompoundAssign {
+    type Out = ();
+
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        // generate left-value pointer once, then the value
+        let lval = self.lval.gen(program, scopes).into_ptr();
+        let op = self.op.gen(program, scopes);
+        let exp = self.exp.gen(program, scopes).into_val(program, scopes);
+        match scopes.types.ty(&self.lval) {
+            Ty::Sized(ty) => generate_sized_update(program, scopes, lval, *ty, op, exp),
+            Ty::Pointer(ty) => generate_pointer_update(program, scopes, lval, ty, op, exp),
+            _ => generate_update(program, scopes, lval, op, exp),
+        }
+    }
+}
+
+impl<'ast> GenerateProgram<'ast> for Increment {
+    type Out = ();
+
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        let lval = self.lval.gen(program, scopes).into_ptr();
+        let one = current_fun!(scopes).new_value(program).integer(1);
+        let op = match self.op {
+            IncOp::Inc(_) => BinaryOp::Add,
+            IncOp::Dec(_) => BinaryOp::Sub,
+        };
+        match scopes.types.ty(&self.lval) {
+            Ty::Pointer(ty) => generate_pointer_update(program, scopes, lval, ty, op, one),
+            Ty::Sized(ty) => generate_sized_update(program, scopes, lval, *ty, op, one),
+            _ => generate_update(program, scopes, lval, op, one),
+        }
     }
 }
 
+/// Generates a load of the given pointer, the binary operation with `rhs`,
+/// and a store of the result back to the pointer.
+fn generate_update(
+    program: &mut Program,
+    scopes: &mut Scopes,
+    ptr: IrValue,
+    op: BinaryOp,
+    rhs: IrValue,
+) {
+    let info = current_fun!(scopes);
+    let load = info.new_value(program).load(ptr);
+    info.push_inst(program, load);
+    let value = info.new_value(program).binary(op, load, rhs);
+    info.push_inst(program, value);
+    let store = info.new_value(program).store(value, ptr);
+    info.push_inst(program, store);
+}
+
+/// Generates a load of the given pointer to a fixed-width integer,
+/// the binary operation with `rhs`, and a store of the result back to the pointer.
+fn generate_sized_update(
+    program: &mut Program,
+    scopes: &mut Scopes,
+    ptr: IrValue,
+    ty: IntTy,
+    op: BinaryOp,
+    rhs: IrValue,
+) {
+    let lhs = values::load(program, scopes, ptr, &Ty::Sized(ty));
+    let value = generate_int_op(program, scopes, Some(ty), op, lhs, rhs);
+    values::store(program, scopes, value, ptr, &Ty::Sized(ty));
+}
+
+/// Generates a load of the given pointer to a pointer, the pointer arithmetic
+/// with `offset`, and a store of the result back to the pointer.
+fn generate_pointer_update(
+    program: &mut Program,
+    scopes: &mut Scopes,
+    ptr: IrValue,
+    ty: &Ty,
+    op: BinaryOp,
+    offset: IrValue,
+) {
+    let info = current_fun!(scopes);
+    let load = info.new_value(program).load(ptr);
+    info.push_inst(program, load);
+    let value = generate_offset(program, scopes, load, ty, op, offset);
+    let info = current_fun!(scopes);
+    let store = info.new_value(program).store(value, ptr);
+    info.push_inst(program, store);
+}
+
 impl<'ast> GenerateProgram<'ast> for ExprStmt {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         if let Some(exp) = &self.exp {
-            exp.gen(program, scopes)?;
+            exp.gen(program, scopes);
         }
-        Ok(())
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for If {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         // generate condition
-        let cond = self.cond.gen(program, scopes)?.into_int(program, scopes)?;
+        let cond = self.cond.gen(program, scopes).into_val(program, scopes);
         // generate branch and then/else basic block
         let info = current_fun_mut!(scopes);
         let then_bb = info.new_basic_block(program, Some("%if_then"));
@@ -366,7 +664,7 @@ impl<'ast> GenerateProgram<'ast> for If {
         info.push_inst(program, br);
         info.push_bb(program, then_bb);
         // generate then statement
-        self.then.gen(program, scopes)?;
+        self.then.gen(program, scopes);
         // generate jump and end basic block
         let info = current_fun_mut!(scopes);
         let end_bb = info.new_basic_block(program, Some("%if_end"));
@@ -375,21 +673,20 @@ impl<'ast> GenerateProgram<'ast> for If {
         info.push_bb(program, else_bb);
         // generate else statement
         if let Some(else_then) = &self.else_then {
-            else_then.gen(program, scopes)?;
+            else_then.gen(program, scopes);
         }
         // generate jump
         let info = current_fun_mut!(scopes);
         let jump = info.new_value(program).jump(end_bb);
         info.push_inst(program, jump);
         info.push_bb(program, end_bb);
-        Ok(())
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for While {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         // generate loop entry basic block
         let info = current_fun_mut!(scopes);
         let entry_bb = info.new_basic_block(program, Some("%while_entry"));
@@ -397,7 +694,7 @@ impl<'ast> GenerateProgram<'ast> for While {
         info.push_inst(program, jump);
         info.push_bb(program, entry_bb);
         // generate condition
-        let cond = self.cond.gen(program, scopes)?.into_int(program, scopes)?;
+        let cond = self.cond.gen(program, scopes).into_val(program, scopes);
         // generate branch and loop body/end basic block
         let info = current_fun_mut!(scopes);
         let body_bb = info.new_basic_block(program, Some("%while_body"));
@@ -406,71 +703,366 @@ impl<'ast> GenerateProgram<'ast> for While {
         info.push_inst(program, br);
         info.push_bb(program, body_bb);
         // generate loop body
-        scopes.loop_info.push((entry_bb, end_bb));
-        self.body.gen(program, scopes)?;
+        enter_loop(scopes, &self.label, end_bb, entry_bb);
+        self.body.gen(program, scopes);
         scopes.loop_info.pop();
         // generate jump
         let info = current_fun_mut!(scopes);
         let jump = info.new_value(program).jump(entry_bb);
         info.push_inst(program, jump);
         info.push_bb(program, end_bb);
-        Ok(())
     }
 }
 
+impl<'ast> GenerateProgram<'ast> for Loop {
+    type Out = ();
+
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        // generate loop body/end basic block
+        let info = current_fun_mut!(scopes);
+        let body_bb = info.new_basic_block(program, Some("%loop_body"));
+        let end_bb = info.new_basic_block(program, Some("%loop_end"));
+        let jump = info.new_value(program).jump(body_bb);
+        info.push_inst(program, jump);
+        info.push_bb(program, body_bb);
+        // generate loop body
+        enter_loop(scopes, &self.label, end_bb, body_bb);
+        self.body.gen(program, scopes);
+        scopes.loop_info.pop();
+        // generate jump
+        let info = current_fun_mut!(scopes);
+        let jump = info.new_value(program).jump(body_bb);
+        info.push_inst(program, jump);
+        info.push_bb(program, end_bb);
+    }
+}
+
+impl<'ast> GenerateProgram<'ast> for DoWhile {
+    type Out = ();
+
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        // generate loop body/condition/end basic block
+        let info = current_fun_mut!(scopes);
+        let body_bb = info.new_basic_block(program, Some("%do_body"));
+        let cond_bb = info.new_basic_block(program, Some("%do_cond"));
+        let end_bb = info.new_basic_block(program, Some("%do_end"));
+        let jump = info.new_value(program).jump(body_bb);
+        info.push_inst(program, jump);
+        info.push_bb(program, body_bb);
+        // generate loop body, `continue` jumps to the condition
+        enter_loop(scopes, &self.label, end_bb, cond_bb);
+        self.body.gen(program, scopes);
+        scopes.loop_info.pop();
+        let info = current_fun_mut!(scopes);
+        let jump = info.new_value(program).jump(cond_bb);
+        info.push_inst(program, jump);
+        info.push_bb(program, cond_bb);
+        // generate condition and branch
+        let cond = self.cond.gen(program, scopes).into_val(program, scopes);
+        let info = current_fun_mut!(scopes);
+        let br = info.new_value(program).branch(cond, body_bb, end_bb);
+        info.push_inst(program, br);
+        info.push_bb(program, end_bb);
+    }
+}
+
+impl<'ast> GenerateProgram<'ast> for For {
+    type Out = ();
+
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        // the induction variables live in their own scope
+        scopes.enter();
+        if let Some(init) = &self.init {
+            init.gen(program, scopes);
+        }
+        generate_loop(
+            program,
+            scopes,
+            &self.label,
+            &self.cond,
+            &self.body,
+            &self.step,
+        );
+        scopes.exit();
+    }
+}
+
+impl<'ast> GenerateProgram<'ast> for ForRange {
+    type Out = ();
+
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        // evaluate the bounds once, before defining the induction variable
+        let start = self.start.gen(program, scopes).into_val(program, scopes);
+        let end = self.end.gen(program, scopes).into_val(program, scopes);
+        let info = current_fun!(scopes);
+        let end_ptr = info.new_alloc(program, Type::get_i32(), None);
+        let store = info.new_value(program).store(end, end_ptr);
+        info.push_inst(program, store);
+        let var = info.new_alloc(program, Type::get_i32(), Some(&self.id.name));
+        let store = info.new_value(program).store(start, var);
+        info.push_inst(program, store);
+        // generate loop in the scope of the induction variable
+        scopes.enter();
+        scopes.new_value(&self.id.name, Value::Value(var));
+        generate_range_loop(program, scopes, &self.label, var, end_ptr, &self.body);
+        scopes.exit();
+    }
+}
+
+/// Generates a loop checking `cond` before each iteration and running `step`
+/// after each iteration, `continue` in `body` jumps to the step.
+fn generate_loop<'ast>(
+    program: &mut Program,
+    scopes: &mut Scopes<'ast>,
+    label: &'ast Option<Label>,
+    cond: &'ast Option<Expr>,
+    body: &'ast Stmt,
+    step: &'ast Option<Stmt>,
+) {
+    // generate condition basic block
+    let info = current_fun_mut!(scopes);
+    let cond_bb = info.new_basic_block(program, Some("%for_cond"));
+    let body_bb = info.new_basic_block(program, Some("%for_body"));
+    let step_bb = info.new_basic_block(program, Some("%for_step"));
+    let end_bb = info.new_basic_block(program, Some("%for_end"));
+    let jump = info.new_value(program).jump(cond_bb);
+    info.push_inst(program, jump);
+    info.push_bb(program, cond_bb);
+    // generate condition, the loop is infinite if it is omitted
+    let branch = match cond {
+        Some(cond) => {
+            let cond = cond.gen(program, scopes).into_val(program, scopes);
+            current_fun!(scopes)
+                .new_value(program)
+                .branch(cond, body_bb, end_bb)
+        }
+        None => current_fun!(scopes).new_value(program).jump(body_bb),
+    };
+    let info = current_fun_mut!(scopes);
+    info.push_inst(program, branch);
+    info.push_bb(program, body_bb);
+    // generate loop body
+    enter_loop(scopes, label, end_bb, step_bb);
+    body.gen(program, scopes);
+    scopes.loop_info.pop();
+    // generate step
+    let info = current_fun_mut!(scopes);
+    let jump = info.new_value(program).jump(step_bb);
+    info.push_inst(program, jump);
+    info.push_bb(program, step_bb);
+    if let Some(step) = step {
+        step.gen(program, scopes);
+    }
+    let info = current_fun_mut!(scopes);
+    let jump = info.new_value(program).jump(cond_bb);
+    info.push_inst(program, jump);
+    info.push_bb(program, end_bb);
+}
+
+/// Generates a loop over the induction variable `var` from its current value
+/// to the value stored at `end_ptr`, exclusive.
+fn generate_range_loop<'ast>(
+    program: &mut Program,
+    scopes: &mut Scopes<'ast>,
+    label: &'ast Option<Label>,
+    var: IrValue,
+    end_ptr: IrValue,
+    body: &'ast Block,
+) {
+    // generate condition
+    let info = current_fun_mut!(scopes);
+    let cond_bb = info.new_basic_block(program, Some("%for_cond"));
+    let body_bb = info.new_basic_block(program, Some("%for_body"));
+    let step_bb = info.new_basic_block(program, Some("%for_step"));
+    let end_bb = info.new_basic_block(program, Some("%for_end"));
+    let jump = info.new_value(program).jump(cond_bb);
+    info.push_inst(program, jump);
+    info.push_bb(program, cond_bb);
+    let cur = info.new_value(program).load(var);
+    info.push_inst(program, cur);
+    let end = info.new_value(program).load(end_ptr);
+    info.push_inst(program, end);
+    let cond = info.new_value(program).binary(BinaryOp::Lt, cur, end);
+    info.push_inst(program, cond);
+    let branch = info.new_value(program).branch(cond, body_bb, end_bb);
+    info.push_inst(program, branch);
+    info.push_bb(program, body_bb);
+    // generate loop body
+    enter_loop(scopes, label, end_bb, step_bb);
+    body.gen(program, scopes);
+    scopes.loop_info.pop();
+    // generate step
+    let info = current_fun_mut!(scopes);
+    let jump = info.new_value(program).jump(step_bb);
+    info.push_inst(program, jump);
+    info.push_bb(program, step_bb);
+    let one = info.new_value(program).integer(1);
+    generate_update(program, scopes, var, BinaryOp::Add, one);
+    let info = current_fun_mut!(scopes);
+    let jump = info.new_value(program).jump(cond_bb);
+    info.push_inst(program, jump);
+    info.push_bb(program, end_bb);
+}
+
+impl<'ast> GenerateProgram<'ast> for Match {
+    type Out = ();
+
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        // generate the value once, all tests compare with it
+        let value = self.exp.gen(program, scopes).into_val(program, scopes);
+        let info = current_fun_mut!(scopes);
+        let end_bb = info.new_basic_block(program, Some("%match_end"));
+        let arm_bbs: Vec<_> = self
+            .arms
+            .iter()
+            .map(|_| info.new_basic_block(program, Some("%match_arm")))
+            .collect();
+        // generate a test basic block for each pattern, until a wildcard
+        let mut test_bb = info.new_basic_block(program, Some("%match_test"));
+        let jump = info.new_value(program).jump(test_bb);
+        info.push_inst(program, jump);
+        'arms: for (arm, &arm_bb) in self.arms.iter().zip(&arm_bbs) {
+            for pat in &arm.pats {
+                current_fun_mut!(scopes).push_bb(program, test_bb);
+                let Some(cond) = pat.gen(program, scopes, value) else {
+                    let info = current_fun!(scopes);
+                    let jump = info.new_value(program).jump(arm_bb);
+                    info.push_inst(program, jump);
+                    break 'arms;
+                };
+                let info = current_fun!(scopes);
+                let next_bb = info.new_basic_block(program, Some("%match_test"));
+                let br = info.new_value(program).branch(cond, arm_bb, next_bb);
+                info.push_inst(program, br);
+                test_bb = next_bb;
+            }
+        }
+        // values that match no pattern skip the statement
+        if !self.arms.iter().any(|arm| arm.is_wildcard()) {
+            let info = current_fun_mut!(scopes);
+            info.push_bb(program, test_bb);
+            let jump = info.new_value(program).jump(end_bb);
+            info.push_inst(program, jump);
+        }
+        // generate arms
+        for (arm, arm_bb) in self.arms.iter().zip(arm_bbs) {
+            current_fun_mut!(scopes).push_bb(program, arm_bb);
+            arm.body.gen(program, scopes);
+            let info = current_fun!(scopes);
+            let jump = info.new_value(program).jump(end_bb);
+            info.push_inst(program, jump);
+        }
+        current_fun_mut!(scopes).push_bb(program, end_bb);
+    }
+}
+
+impl Pattern {
+    /// Generates the test of the pattern against the given value,
+    /// returns `None` if the pattern matches all values.
+    fn gen(&self, program: &mut Program, scopes: &mut Scopes, value: IrValue) -> Option<IrValue> {
+        let info = current_fun!(scopes);
+        let cond = match self {
+            Self::Value(v) => {
+                let v = v.eval(scopes);
+                let v = info.new_value(program).integer(v);
+                let cond = info.new_value(program).binary(BinaryOp::Eq, value, v);
+                info.push_inst(program, cond);
+                cond
+            }
+            Self::Range(lo, hi) => {
+                let lo = info.new_value(program).integer(lo.eval(scopes));
+                let hi = info.new_value(program).integer(hi.eval(scopes));
+                let ge = info.new_value(program).binary(BinaryOp::Ge, value, lo);
+                info.push_inst(program, ge);
+                let le = info.new_value(program).binary(BinaryOp::Le, value, hi);
+                info.push_inst(program, le);
+                let cond = info.new_value(program).binary(BinaryOp::And, ge, le);
+                info.push_inst(program, cond);
+                cond
+            }
+            Self::Wildcard(_) => return None,
+        };
+        Some(cond)
+    }
+}
+
+impl PatValue {
+    /// Evaluates the value of the pattern.
+    fn eval(&self, scopes: &Scopes) -> i32 {
+        match self {
+            Self::Number(num) => num.value,
+            Self::Const(id) => match scopes.value(&id.name) {
+                Value::Const(v) => *v,
+                _ => unreachable!(),
+            },
+        }
+    }
+}
+
+/// Pushes the loop information.
+fn enter_loop<'ast>(
+    scopes: &mut Scopes<'ast>,
+    label: &'ast Option<Label>,
+    break_bb: BasicBlock,
+    continue_bb: BasicBlock,
+) {
+    scopes.loop_info.push(LoopInfo {
+        label: label.as_ref(),
+        break_bb,
+        continue_bb,
+    });
+}
+
 impl<'ast> GenerateProgram<'ast> for Break {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         // jump to the end of loop
-        let info = &mut current_fun_mut!(scopes);
-        let (_, end) = 
-            // scopes.loop_info.last()
-            (E0003::E0003 { ast: self }).run(scopes);
-        let jump = info.new_value(program).jump(*end);
+        let label = self.label.as_ref().map(|l| l.name.as_str());
+        let end = scopes.loop_info(label).unwrap().break_bb;
+        let info = current_fun_mut!(scopes);
+        let jump = info.new_value(program).jump(end);
         info.push_inst(program, jump);
         // push new basic block
         let next = info.new_basic_block(program, None);
         info.push_bb(program, next);
-        Ok(())
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for Continue {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
-        // jump to the entry of loop
-        let info = &mut current_fun_mut!(scopes);
-        
-        // scopes.loop_info.last().
-        let (entry, _) = 
-            (E0005::E0005 { ast: self }).run(scopes);
-            
-        let jump = info.new_value(program).jump(*entry);
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        // jump to the continue target of loop
+        let label = self.label.as_ref().map(|l| l.name.as_str());
+        let entry = scopes.loop_info(label).unwrap().continue_bb;
+        let info = current_fun_mut!(scopes);
+        let jump = info.new_value(program).jump(entry);
         info.push_inst(program, jump);
-        
         // push new basic block
         let next = info.new_basic_block(program, None);
         info.push_bb(program, next);
-        Ok(())
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for Return {
     type Out = ();
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         if let Some(ret_val) = current_fun!(scopes).ret_val() {
             // generate store
             if let Some(val) = &self.exp {
-                let value = val.gen(program, scopes)?.into_int(program, scopes)?;
+                let value = val.gen(program, scopes).into_val(program, scopes);
                 let info = current_fun!(scopes);
                 let store = info.new_value(program).store(value, ret_val);
                 info.push_inst(program, store);
             }
-        } else if self.exp.is_some() {
-            return (E0004::E0004 { ast: self.exp }).run()
+        } else if let (Some(ret_ptr), Some(val)) = (current_fun!(scopes).ret_ptr(), &self.exp) {
+            let value = val.gen(program, scopes).into_val(program, scopes);
+            let info = current_fun!(scopes);
+            let ptr = info.new_value(program).load(ret_ptr);
+            info.push_inst(program, ptr);
+            values::store(program, scopes, value, ptr, &Ty::Sized(IntTy::I64));
         }
         // jump to the end basic block
         let info = &mut current_fun_mut!(scopes);
@@ -479,30 +1071,82 @@ impl<'ast> GenerateProgram<'ast> for Return {
         // push new basic block
         let next = info.new_basic_block(program, None);
         info.push_bb(program, next);
-        Ok(())
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for Expr {
-    type Out = ExprValue;
+    type Out = ExprValue<'ast>;
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         self.lor.gen(program, scopes)
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for LVal {
-    type Out = ExprValue;
-
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    type Out = ExprValue<'ast>;
+
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        let id = match &self.base {
+            LValBase::Var(id) => id,
+            LValBase::Expr(exp) => {
+                let value = exp.gen(program, scopes);
+                return self.gen_expr(program, scopes, value);
+            }
+        };
         // handle constant
-        let mut value = match scopes.value(&self.id.name)? {
-            Value::Value(value) => *value,
+        let value = match scopes.value(&id.name).clone() {
+            Value::Value(value) => self.gen_var(program, scopes, value, None),
+            Value::Struct(value, name) => self.gen_var(program, scopes, value, Some(name)),
+            Value::Pointer(ptr, ty, lens) => self.gen_pointer_var(program, scopes, ptr, ty, &lens),
+            Value::Bool(value) => ExprValue::BoolPtr(value),
+            Value::Sized(value, ty, lens) => self.gen_sized(program, scopes, value, ty, &lens),
             Value::Const(num) => {
-                // self.indices.is_empty()
-                (E0006::E0006 { ast: self }).run(program, scopes, num)?;
+                return ExprValue::Int(current_fun!(scopes).new_value(program).integer(num))
             }
         };
+        self.gen_derefs(program, scopes, value)
+    }
+}
+
+impl<'ast> LVal {
+    /// Generates indices, fields and dereferences of the value of the expression
+    /// the left value starts from, which is a pointer, an array or a struct.
+    fn gen_expr(
+        &'ast self,
+        program: &mut Program,
+        scopes: &mut Scopes<'ast>,
+        value: ExprValue<'ast>,
+    ) -> ExprValue<'ast> {
+        let value = match value {
+            ExprValue::StructPtr(ptr, name) => self.gen_fields(program, scopes, ptr, name),
+            value => self.gen_pointer(program, scopes, value, &self.indices),
+        };
+        self.gen_derefs(program, scopes, value)
+    }
+
+    /// Generates the dereferences applied after the indices and fields.
+    fn gen_derefs(
+        &'ast self,
+        program: &mut Program,
+        scopes: &mut Scopes<'ast>,
+        mut value: ExprValue<'ast>,
+    ) -> ExprValue<'ast> {
+        // handle pointer dereference
+        for _ in 0..self.derefs {
+            value = value.deref(program, scopes);
+        }
+        value
+    }
+
+    /// Generates indices and fields of a variable
+    /// which is an integer, a struct or an array.
+    fn gen_var(
+        &'ast self,
+        program: &mut Program,
+        scopes: &mut Scopes<'ast>,
+        mut value: IrValue,
+        struct_name: Option<&'ast str>,
+    ) -> ExprValue<'ast> {
         // check type
         let mut is_ptr_ptr = false;
         let mut dims = match scopes.ty(program, value).kind() {
@@ -523,6 +1167,10 @@ impl<'ast> GenerateProgram<'ast> for LVal {
             }
             _ => 0,
         };
+        // the innermost dimension of a struct is its words
+        if struct_name.is_some() {
+            dims -= 1;
+        }
         // generate load for array parameter
         if is_ptr_ptr {
             let info = current_fun!(scopes);
@@ -531,12 +1179,9 @@ impl<'ast> GenerateProgram<'ast> for LVal {
         }
         // handle array dereference
         for (i, index) in self.indices.iter().enumerate() {
-            // check if dereferencing integer
-            // if dims == 0
-            (E0007::E0007 { ast: self }).run(dims)?;
             dims -= 1;
             // generate index
-            let index = index.gen(program, scopes)?.into_val(program, scopes)?;
+            let index = index.gen(program, scopes).into_word(program, scopes);
             // generate pointer calculation
             let info = current_fun!(scopes);
             value = if is_ptr_ptr && i == 0 {
@@ -546,9 +1191,19 @@ impl<'ast> GenerateProgram<'ast> for LVal {
             };
             info.push_inst(program, value);
         }
+        // handle struct and field access
+        if let Some(name) = struct_name {
+            if !is_ptr_ptr || !self.indices.is_empty() {
+                let info = current_fun!(scopes);
+                let zero = info.new_value(program).integer(0);
+                value = info.new_value(program).get_elem_ptr(value, zero);
+                info.push_inst(program, value);
+            }
+            return self.gen_fields(program, scopes, value, name);
+        }
         // generate pointer calculation for funtion arguments
         if dims == 0 {
-            Ok(ExprValue::IntPtr(value))
+            ExprValue::IntPtr(value)
         } else {
             if !is_ptr_ptr || !self.indices.is_empty() {
                 let info = current_fun!(scopes);
@@ -556,153 +1211,661 @@ impl<'ast> GenerateProgram<'ast> for LVal {
                 value = info.new_value(program).get_elem_ptr(value, zero);
                 info.push_inst(program, value);
             }
-            Ok(ExprValue::ArrPtr(value))
+            ExprValue::ArrPtr(value)
+        }
+    }
+
+    /// Generates indices of a fixed-width integer variable or array, which has
+    /// no fields. Arrays with one dimension left are pointers to their first element.
+    fn gen_sized(
+        &'ast self,
+        program: &mut Program,
+        scopes: &mut Scopes<'ast>,
+        value: IrValue,
+        ty: IntTy,
+        lens: &[usize],
+    ) -> ExprValue<'ast> {
+        if lens.is_empty() && ty == IntTy::I64 {
+            return ExprValue::SizedPtr(values::long_ptr(program, scopes, value), ty);
+        }
+        if lens.is_empty() {
+            return ExprValue::SizedPtr(value, ty);
+        }
+        // the elements are packed, so the indices are
+        // flattened into an offset from the first element
+        let offset = flatten_indices(program, scopes, &self.indices, lens);
+        let info = current_fun!(scopes);
+        let zero = info.new_value(program).integer(0);
+        let mut ptr = info.new_value(program).get_elem_ptr(value, zero);
+        info.push_inst(program, ptr);
+        if let Some(offset) = offset {
+            ptr = generate_offset(program, scopes, ptr, &Ty::Sized(ty), BinaryOp::Add, offset);
+        }
+        match lens.len() - self.indices.len() {
+            0 => ExprValue::SizedPtr(ptr, ty),
+            1 => ExprValue::Ptr(ptr, Ty::Sized(ty)),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Generates indices and fields of a pointer variable or array, the indices
+    /// of arrays are flattened into an offset from the first element, and
+    /// the remaining indices are offsets from the pointer.
+    fn gen_pointer_var(
+        &'ast self,
+        program: &mut Program,
+        scopes: &mut Scopes<'ast>,
+        ptr: IrValue,
+        ty: Ty<'ast>,
+        lens: &[usize],
+    ) -> ExprValue<'ast> {
+        if lens.is_empty() {
+            return self.gen_pointer(program, scopes, ExprValue::PtrPtr(ptr, ty), &self.indices);
+        }
+        let (indices, rest) = self.indices.split_at(lens.len().min(self.indices.len()));
+        let offset = flatten_indices(program, scopes, indices, lens);
+        let info = current_fun!(scopes);
+        let zero = info.new_value(program).integer(0);
+        let mut ptr = info.new_value(program).get_elem_ptr(ptr, zero);
+        info.push_inst(program, ptr);
+        let elem = Ty::Pointer(Box::new(ty));
+        if let Some(offset) = offset {
+            ptr = generate_offset(program, scopes, ptr, &elem, BinaryOp::Add, offset);
+        }
+        // arrays with one dimension left are pointers to their first element
+        if indices.len() < lens.len() {
+            return ExprValue::Ptr(ptr, elem);
+        }
+        let value = ExprValue::Ptr(ptr, elem).deref(program, scopes);
+        self.gen_pointer(program, scopes, value, rest)
+    }
+
+    /// Generates indices and fields of a pointer, indices are offsets
+    /// from the pointer and fields of structs are accessed through the pointer.
+    fn gen_pointer(
+        &'ast self,
+        program: &mut Program,
+        scopes: &mut Scopes<'ast>,
+        mut value: ExprValue<'ast>,
+        indices: &'ast [Expr],
+    ) -> ExprValue<'ast> {
+        for index in indices {
+            let (ptr, ty) = value.into_pointer(program, scopes);
+            let index = index.gen(program, scopes).into_word(program, scopes);
+            let ptr = generate_offset(program, scopes, ptr, &ty, BinaryOp::Add, index);
+            value = ExprValue::Ptr(ptr, ty).deref(program, scopes);
+        }
+        if self.fields.is_empty() {
+            return value;
+        }
+        if let ExprValue::Ptr(_, Ty::Struct(_)) | ExprValue::PtrPtr(_, Ty::Struct(_)) = value {
+            value = value.deref(program, scopes);
+        }
+        match value {
+            ExprValue::StructPtr(ptr, name) => self.gen_fields(program, scopes, ptr, name),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Generates field accesses from the given pointer to the first word of a struct.
+    fn gen_fields(
+        &'ast self,
+        program: &mut Program,
+        scopes: &mut Scopes<'ast>,
+        mut ptr: IrValue,
+        name: &'ast str,
+    ) -> ExprValue<'ast> {
+        let ty = Ty::Struct(name);
+        let mut ty = &ty;
+        let mut dims_left = 0;
+        for access in &self.fields {
+            // fields of structs are accessed through pointer fields
+            if let Ty::Pointer(base) = ty {
+                let info = current_fun!(scopes);
+                ptr = info.new_value(program).load(ptr);
+                info.push_inst(program, ptr);
+                ty = base;
+            }
+            // get field layout, fields narrower than
+            // a word are not always aligned to words
+            let Ty::Struct(name) = ty else { unreachable!() };
+            let field = &scopes.struct_info(name).fields[access.id.name.as_str()];
+            let offset = field.offset as i32;
+            ptr = match offset % 4 {
+                0 => get_ptr(program, scopes, ptr, offset / 4),
+                _ => {
+                    let offset = current_fun!(scopes).new_value(program).integer(offset);
+                    call_intrinsic(program, scopes, Intrinsic::Offset, vec![ptr, offset])
+                }
+            };
+            // handle array dereference
+            let lens = array_lens(&field.ty);
+            ty = field.ty.elem().0;
+            if let Ty::Pointer(_) = ty {
+                ptr = cast_ptr(program, scopes, ptr, ty);
+            }
+            if let Some(offset) = flatten_indices(program, scopes, &access.indices, &lens) {
+                ptr = generate_offset(program, scopes, ptr, ty, BinaryOp::Add, offset);
+            }
+            dims_left = lens.len() - access.indices.len();
+        }
+        match (ty, dims_left) {
+            (Ty::Struct(name), _) => ExprValue::StructPtr(ptr, name),
+            (Ty::Sized(ty), 0) => ExprValue::SizedPtr(ptr, *ty),
+            (Ty::Sized(ty), _) => ExprValue::Ptr(ptr, Ty::Sized(*ty)),
+            (Ty::Pointer(ty), 0) => ExprValue::PtrPtr(ptr, (**ty).clone()),
+            (Ty::Pointer(_), _) => ExprValue::Ptr(ptr, ty.clone()),
+            (_, 0) => ExprValue::IntPtr(ptr),
+            (_, _) => ExprValue::ArrPtr(ptr),
         }
     }
 }
 
+/// Converts the pointer to a word of a struct into
+/// a pointer to the pointer of the given type in the word.
+fn cast_ptr(program: &mut Program, scopes: &mut Scopes, ptr: IrValue, ty: &Ty) -> IrValue {
+    let intrinsic = Intrinsic::Cast(pointer_depth(ty));
+    if !scopes.intrinsics.contains_key(&intrinsic) {
+        declare_intrinsic(program, scopes, intrinsic);
+    }
+    call_intrinsic(program, scopes, intrinsic, vec![ptr])
+}
+
+/// Converts the pointer to a value of the given type into its address.
+fn generate_addr(program: &mut Program, scopes: &mut Scopes, ptr: IrValue, ty: &Ty) -> IrValue {
+    let intrinsic = Intrinsic::Addr(pointer_depth(ty));
+    if !scopes.intrinsics.contains_key(&intrinsic) {
+        declare_intrinsic(program, scopes, intrinsic);
+    }
+    call_intrinsic(program, scopes, intrinsic, vec![ptr])
+}
+
+/// Returns the number of `*` of the Koopa IR type of pointers
+/// to values of the given type.
+fn pointer_depth(ty: &Ty) -> usize {
+    let mut depth = 1;
+    let mut base = ty;
+    while let Ty::Pointer(ty) = base {
+        depth += 1;
+        base = ty;
+    }
+    depth
+}
+
+/// Generates the offset from the first element of an array with
+/// the given dimension lengths to the indexed element, the stride of
+/// a dimension is the size of the remaining dimensions.
+/// Returns `None` if there are no indices.
+fn flatten_indices<'ast>(
+    program: &mut Program,
+    scopes: &mut Scopes<'ast>,
+    indices: &'ast [Expr],
+    lens: &[usize],
+) -> Option<IrValue> {
+    let mut offset = None;
+    for (i, index) in indices.iter().enumerate() {
+        let mut index = index.gen(program, scopes).into_word(program, scopes);
+        let info = current_fun!(scopes);
+        let stride = lens[i + 1..].iter().product::<usize>();
+        if stride != 1 {
+            let stride = info.new_value(program).integer(stride as i32);
+            index = info.new_value(program).binary(BinaryOp::Mul, index, stride);
+            info.push_inst(program, index);
+        }
+        if let Some(offset) = offset {
+            index = info.new_value(program).binary(BinaryOp::Add, offset, index);
+            info.push_inst(program, index);
+        }
+        offset = Some(index);
+    }
+    offset
+}
+
+/// Returns the lengths of the dimensions of an array type,
+/// other types have no dimensions.
+fn array_lens(mut ty: &Ty) -> Vec<usize> {
+    let mut lens = Vec::new();
+    while let Ty::Array(elem, len) = ty {
+        lens.push(len.unwrap_or(0));
+        ty = elem;
+    }
+    lens
+}
+
+/// Generates pointer arithmetic, `offset` is the number of values of the given type
+/// to add or subtract, values of structs and `I64` values are several words, and
+/// pointers to fixed-width integers narrower than a word are offset by bytes.
+fn generate_offset(
+    program: &mut Program,
+    scopes: &Scopes,
+    ptr: IrValue,
+    ty: &Ty,
+    op: BinaryOp,
+    offset: IrValue,
+) -> IrValue {
+    let mut offset = values::low_word(program, scopes, offset);
+    let info = current_fun!(scopes);
+    let size = match ty {
+        Ty::Struct(name) => Some(scopes.struct_info(name).size),
+        Ty::Sized(ty) if ty.size() == 2 || *ty == IntTy::I64 => Some(2),
+        _ => None,
+    };
+    if let Some(size) = size {
+        let size = info.new_value(program).integer(size as i32);
+        offset = info.new_value(program).binary(BinaryOp::Mul, offset, size);
+        info.push_inst(program, offset);
+    }
+    if op == BinaryOp::Sub {
+        let zero = info.new_value(program).integer(0);
+        offset = info.new_value(program).binary(BinaryOp::Sub, zero, offset);
+        info.push_inst(program, offset);
+    }
+    match ty {
+        Ty::Sized(ty) if ty.size() < 4 => {
+            call_intrinsic(program, scopes, Intrinsic::Offset, vec![ptr, offset])
+        }
+        _ => {
+            let ptr = info.new_value(program).get_ptr(ptr, offset);
+            info.push_inst(program, ptr);
+            ptr
+        }
+    }
+}
+
+/// Generates a pointer that is `offset` words after the given pointer.
+fn get_ptr(program: &mut Program, scopes: &Scopes, ptr: IrValue, offset: i32) -> IrValue {
+    if offset == 0 {
+        return ptr;
+    }
+    let info = current_fun!(scopes);
+    let offset = info.new_value(program).integer(offset);
+    let ptr = info.new_value(program).get_ptr(ptr, offset);
+    info.push_inst(program, ptr);
+    ptr
+}
+
 impl<'ast> GenerateProgram<'ast> for PrimaryExpr {
-    type Out = ExprValue;
+    type Out = ExprValue<'ast>;
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         match self {
             Self::Expr(exp) => exp.gen(program, scopes),
             Self::LVal(lval) => lval.gen(program, scopes),
-            Self::Number(num) => Ok(ExprValue::Int(
-                current_fun!(scopes).new_value(program).integer(num.value),
-            )),
+            Self::Number(num) => {
+                ExprValue::Int(current_fun!(scopes).new_value(program).integer(num.value))
+            }
+            Self::Bool(b) => ExprValue::Bool(
+                current_fun!(scopes)
+                    .new_value(program)
+                    .integer(b.value as i32),
+            ),
+            Self::Str(s) => s.gen(program, scopes),
+            Self::If(exp) => exp.gen(program, scopes),
+        }
+    }
+}
+
+impl<'ast> GenerateProgram<'ast> for Str {
+    type Out = ExprValue<'ast>;
+
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        // generate a zero-terminated global array, every literal has its own array
+        // since the arrays can be modified through array parameters
+        let elems = self
+            .value
+            .iter()
+            .chain(Some(&0))
+            .map(|&c| program.new_value().integer(c as i32))
+            .collect();
+        let init = program.new_value().aggregate(elems);
+        let array = program.new_value().global_alloc(init);
+        program.set_value_name(array, Some(format!("@__str_{}", scopes.str_count)));
+        scopes.str_count += 1;
+        // get pointer to the first element
+        let info = current_fun!(scopes);
+        let zero = info.new_value(program).integer(0);
+        let ptr = info.new_value(program).get_elem_ptr(array, zero);
+        info.push_inst(program, ptr);
+        ExprValue::ArrPtr(ptr)
+    }
+}
+
+impl<'ast> GenerateProgram<'ast> for IfExpr {
+    type Out = ExprValue<'ast>;
+
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        // generate condition
+        let cond = self.cond.gen(program, scopes).into_val(program, scopes);
+        // generate branch and then/else/end basic block
+        let info = current_fun_mut!(scopes);
+        let then_bb = info.new_basic_block(program, Some("%if_then"));
+        let else_bb = info.new_basic_block(program, Some("%if_else"));
+        let end_bb = info.new_basic_block(program, Some("%if_end"));
+        let br = info.new_value(program).branch(cond, then_bb, else_bb);
+        info.push_inst(program, br);
+        info.push_bb(program, then_bb);
+        // generate then arm, stores the result if it is not `Unit`,
+        // `I64` results are stored in a temporary of two words
+        let ty = match scopes.types.ty(&self.then.value) {
+            ty @ Ty::Sized(IntTy::I64) => ty,
+            _ => &Ty::Int,
+        };
+        let then = self.then.gen(program, scopes);
+        let result = match then {
+            ExprValue::Void => None,
+            then => {
+                let then = then.into_val(program, scopes);
+                let result = match ty {
+                    Ty::Int => current_fun!(scopes).new_alloc(program, Type::get_i32(), None),
+                    _ => values::alloc_long(program, scopes),
+                };
+                values::store(program, scopes, then, result, ty);
+                Some(result)
+            }
+        };
+        let info = current_fun_mut!(scopes);
+        let jump = info.new_value(program).jump(end_bb);
+        info.push_inst(program, jump);
+        info.push_bb(program, else_bb);
+        // generate else arm, which has the same type
+        let else_then = match &self.else_then {
+            ElseExpr::Block(block) => block.gen(program, scopes),
+            ElseExpr::If(exp) => exp.gen(program, scopes),
+        };
+        if let Some(result) = result {
+            let else_then = else_then.into_val(program, scopes);
+            values::store(program, scopes, else_then, result, ty);
         }
+        let info = current_fun_mut!(scopes);
+        let jump = info.new_value(program).jump(end_bb);
+        info.push_inst(program, jump);
+        info.push_bb(program, end_bb);
+        // generate load
+        let Some(result) = result else {
+            return ExprValue::Void;
+        };
+        let load = values::load(program, scopes, result, ty);
+        ExprValue::from_ty(load, scopes.types.ty(&self.then.value))
+    }
+}
+
+impl<'ast> GenerateProgram<'ast> for ValueBlock {
+    type Out = ExprValue<'ast>;
+
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        scopes.enter();
+        for item in &self.items {
+            item.gen(program, scopes);
+        }
+        let value = self.value.gen(program, scopes);
+        scopes.exit();
+        value
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for UnaryExpr {
-    type Out = ExprValue;
+    type Out = ExprValue<'ast>;
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         match self {
             Self::Primary(exp) => exp.gen(program, scopes),
             Self::Call(call) => call.gen(program, scopes),
-            Self::Unary(op, exp) => {
-                let exp = exp.gen(program, scopes)?.into_int(program, scopes)?;
+            Self::Unary(UnaryOp::LNot(_), exp) => {
+                let exp = exp.gen(program, scopes).into_val(program, scopes);
                 let info = current_fun!(scopes);
                 let zero = info.new_value(program).integer(0);
+                let value = info.new_value(program).binary(BinaryOp::Eq, exp, zero);
+                info.push_inst(program, value);
+                ExprValue::Bool(value)
+            }
+            Self::Unary(op, exp) => {
+                let ty = int_ty(scopes.types.ty(self));
+                let exp = exp.gen(program, scopes).into_val(program, scopes);
+                let info = current_fun!(scopes);
                 let value = match op {
-                    UnaryOp::Neg(_) => info.new_value(program).binary(BinaryOp::Sub, zero, exp),
-                    UnaryOp::LNot(_) => info.new_value(program).binary(BinaryOp::Eq, exp, zero),
+                    UnaryOp::Neg(_) => {
+                        let zero = info.new_value(program).integer(0);
+                        generate_int_op(program, scopes, ty, BinaryOp::Sub, zero, exp)
+                    }
+                    UnaryOp::Not(_) => {
+                        let ones = ty.map_or(-1, |ty| ty.wrap(-1));
+                        let ones = info.new_value(program).integer(ones);
+                        generate_int_op(program, scopes, ty, BinaryOp::Xor, exp, ones)
+                    }
+                    UnaryOp::LNot(_) => unreachable!(),
                 };
-                info.push_inst(program, value);
-                Ok(ExprValue::Int(value))
+                ExprValue::Int(value)
             }
+            Self::Cast(exp, _) => {
+                // Bools are already `0` or `1`, and integers keep their bit patterns
+                // when converted to types that are not narrower
+                let types = scopes.types;
+                let from = types.ty(&**exp);
+                let mut value = exp.gen(program, scopes).into_val(program, scopes);
+                match (from, types.ty(self)) {
+                    (_, Ty::Sized(IntTy::I64)) => {
+                        return ExprValue::Int(generate_extend(program, scopes, value, from))
+                    }
+                    // `I64` values are truncated, and are `true` if any bit is set
+                    (Ty::Sized(IntTy::I64), Ty::Bool) => {
+                        let (lo, hi) = values::words(program, scopes, value);
+                        value = generate_int_op(program, scopes, None, BinaryOp::Or, lo, hi);
+                    }
+                    (Ty::Sized(IntTy::I64), _) => value = values::low_word(program, scopes, value),
+                    _ => {}
+                }
+                match types.ty(self) {
+                    Ty::Bool if *from == Ty::Bool => ExprValue::Bool(value),
+                    Ty::Bool => {
+                        let info = current_fun!(scopes);
+                        let zero = info.new_value(program).integer(0);
+                        let value = info.new_value(program).binary(BinaryOp::NotEq, value, zero);
+                        info.push_inst(program, value);
+                        ExprValue::Bool(value)
+                    }
+                    Ty::Sized(ty) => ExprValue::Int(generate_wrap(program, scopes, value, *ty)),
+                    _ => ExprValue::Int(value),
+                }
+            }
+            Self::AddrOf(lval) => lval.gen(program, scopes).into_addr(program, scopes),
         }
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for FuncCall {
-    type Out = ExprValue;
-
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
-        // get funtion from scope
-        let fun = scopes.fun(&self.id.name)?;
-        // get funtion type
-        let (params_ty, is_void) = match program.func(fun).ty().kind() {
-            TypeKind::Function(params, ret) => (params.clone(), ret.is_unit()),
-            _ => unreachable!(),
-        };
-        // generate arguments
-        let args = self
-            .args
-            .iter()
-            .map(|a| a.gen(program, scopes)?.into_val(program, scopes))
-            .collect::<Result<Vec<_>>>()?;
-        // check argument types
-        // if params_ty.len() != args.len() {
-        (E0008::E0008 { ast: self }).run(params_ty, &args)?;
-        (E0009::E0009 { ast: self }).run(params_ty, &args, program, scopes)?;
+    type Out = ExprValue<'ast>;
 
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        let fun = scopes.fun(&self.id.name);
+        let TypeKind::Function(params_ty, _) = program.func(fun).ty().kind() else {
+            unreachable!()
+        };
+        let params_ty = params_ty.clone();
+        // `I64` return values are stored to a temporary passed as the first argument
+        let ty = scopes.types.ty(self);
+        let mut args = Vec::new();
+        if *ty == Ty::Sized(IntTy::I64) {
+            args.push(values::alloc_long(program, scopes));
+        }
+        // generate arguments, integers passed as `I64` values are words
+        // only if they are integer constants, which are converted
+        for (exp, param_ty) in self.args.iter().zip(&params_ty[args.len()..]) {
+            let mut arg = exp.gen(program, scopes).into_val(program, scopes);
+            if let TypeKind::Pointer(_) = param_ty.kind() {
+                arg = values::into_long(program, scopes, arg);
+            }
+            args.push(arg);
+        }
         // generate funtion call
+        let ret = (*ty == Ty::Sized(IntTy::I64)).then(|| args[0]);
         let info = current_fun!(scopes);
         let call = info.new_value(program).call(fun, args);
         info.push_inst(program, call);
-        // return value if not void
-        if is_void {
-            Ok(ExprValue::Void)
-        } else {
-            Ok(ExprValue::Int(call))
+        match ret {
+            Some(ret) => ExprValue::Int(ret),
+            None => ExprValue::from_ty(call, ty),
         }
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for MulExpr {
-    type Out = ExprValue;
+    type Out = ExprValue<'ast>;
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         match self {
             Self::Unary(exp) => exp.gen(program, scopes),
-            Self::MulUnary(lhs, op, rhs) => {
-                let lhs = lhs.gen(program, scopes)?.into_int(program, scopes)?;
-                let rhs = rhs.gen(program, scopes)?.into_int(program, scopes)?;
-                let op = op.gen(program, scopes)?;
-                let info = current_fun!(scopes);
-                let value = info.new_value(program).binary(op, lhs, rhs);
-                info.push_inst(program, value);
-                Ok(ExprValue::Int(value))
+            Self::MulUnary(lhs_exp, op, rhs_exp) => {
+                let ty = int_ty(scopes.types.ty(self));
+                let lhs = lhs_exp.gen(program, scopes).into_val(program, scopes);
+                let rhs = rhs_exp.gen(program, scopes).into_val(program, scopes);
+                let op = op.gen(program, scopes);
+                let value = generate_int_op(program, scopes, ty, op, lhs, rhs);
+                ExprValue::Int(value)
             }
         }
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for AddExpr {
-    type Out = ExprValue;
+    type Out = ExprValue<'ast>;
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         match self {
             Self::Mul(exp) => exp.gen(program, scopes),
-            Self::AddMul(lhs, op, rhs) => {
-                let lhs = lhs.gen(program, scopes)?.into_int(program, scopes)?;
-                let rhs = rhs.gen(program, scopes)?.into_int(program, scopes)?;
-                let op = op.gen(program, scopes)?;
-                let info = current_fun!(scopes);
-                let value = info.new_value(program).binary(op, lhs, rhs);
-                info.push_inst(program, value);
-                Ok(ExprValue::Int(value))
+            Self::AddMul(lhs_exp, op, rhs_exp) => {
+                let lhs = lhs_exp.gen(program, scopes).into_rvalue(program, scopes);
+                let rhs = rhs_exp.gen(program, scopes).into_rvalue(program, scopes);
+                let op = op.gen(program, scopes);
+                let ty = scopes.types.ty(self);
+                if let Ty::Pointer(_) = ty {
+                    let (ptr, n) = match scopes.types.ty(&**lhs_exp) {
+                        Ty::Pointer(_) => (lhs, rhs),
+                        _ => (rhs, lhs),
+                    };
+                    let (ptr, ty) = ptr.into_pointer(program, scopes);
+                    let n = n.into_val(program, scopes);
+                    let ptr = generate_offset(program, scopes, ptr, &ty, op, n);
+                    return ExprValue::Ptr(ptr, ty);
+                }
+                let ty = int_ty(ty);
+                let lhs = lhs.into_val(program, scopes);
+                let rhs = rhs.into_val(program, scopes);
+                let value = generate_int_op(program, scopes, ty, op, lhs, rhs);
+                ExprValue::Int(value)
             }
         }
     }
 }
 
-impl<'ast> GenerateProgram<'ast> for RelExpr {
-    type Out = ExprValue;
+impl<'ast> GenerateProgram<'ast> for ShiftExpr {
+    type Out = ExprValue<'ast>;
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         match self {
             Self::Add(exp) => exp.gen(program, scopes),
-            Self::RelAdd(lhs, op, rhs) => {
-                let lhs = lhs.gen(program, scopes)?.into_int(program, scopes)?;
-                let rhs = rhs.gen(program, scopes)?.into_int(program, scopes)?;
-                let op = op.gen(program, scopes)?;
-                let info = current_fun!(scopes);
-                let value = info.new_value(program).binary(op, lhs, rhs);
-                info.push_inst(program, value);
-                Ok(ExprValue::Int(value))
+            Self::ShiftAdd(lhs, op, rhs) => {
+                let ty = int_ty(scopes.types.ty(self));
+                let lhs = lhs.gen(program, scopes).into_val(program, scopes);
+                let rhs = rhs.gen(program, scopes).into_val(program, scopes);
+                let op = op.gen(program, scopes);
+                let value = generate_int_op(program, scopes, ty, op, lhs, rhs);
+                ExprValue::Int(value)
+            }
+        }
+    }
+}
+
+impl<'ast> GenerateProgram<'ast> for RelExpr {
+    type Out = ExprValue<'ast>;
+
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        match self {
+            Self::Shift(exp) => exp.gen(program, scopes),
+            Self::RelShift(lhs_exp, op, rhs_exp) => {
+                let operand_ty = scopes.types.operand_ty(self);
+                let mut lhs = lhs_exp.gen(program, scopes).into_val(program, scopes);
+                let mut rhs = rhs_exp.gen(program, scopes).into_val(program, scopes);
+                // pointers are compared by their addresses
+                if let Ty::Pointer(base) = operand_ty {
+                    lhs = generate_addr(program, scopes, lhs, base);
+                    rhs = generate_addr(program, scopes, rhs, base);
+                }
+                let op = op.gen(program, scopes);
+                let value = generate_int_op(program, scopes, int_ty(operand_ty), op, lhs, rhs);
+                ExprValue::Bool(value)
             }
         }
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for EqExpr {
-    type Out = ExprValue;
+    type Out = ExprValue<'ast>;
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         match self {
             Self::Rel(exp) => exp.gen(program, scopes),
-            Self::EqRel(lhs, op, rhs) => {
-                let lhs = lhs.gen(program, scopes)?.into_int(program, scopes)?;
-                let rhs = rhs.gen(program, scopes)?.into_int(program, scopes)?;
-                let op = op.gen(program, scopes)?;
-                let info = current_fun!(scopes);
-                let value = info.new_value(program).binary(op, lhs, rhs);
-                info.push_inst(program, value);
-                Ok(ExprValue::Int(value))
+            Self::EqRel(lhs_exp, op, rhs_exp) => {
+                let operand_ty = scopes.types.operand_ty(self);
+                let mut lhs = lhs_exp.gen(program, scopes).into_val(program, scopes);
+                let mut rhs = rhs_exp.gen(program, scopes).into_val(program, scopes);
+                // pointers are compared by their addresses
+                if let Ty::Pointer(base) = operand_ty {
+                    lhs = generate_addr(program, scopes, lhs, base);
+                    rhs = generate_addr(program, scopes, rhs, base);
+                }
+                let op = op.gen(program, scopes);
+                let value = generate_int_op(program, scopes, int_ty(operand_ty), op, lhs, rhs);
+                ExprValue::Bool(value)
+            }
+        }
+    }
+}
+
+/// Generates bitwise operators.
+macro_rules! generate_bitwise_ops {
+    ($exp:expr, $lhs:expr, $rhs:expr, $program:expr, $scopes:expr, $op:expr) => {{
+        let ty = int_ty($scopes.types.ty($exp));
+        let lhs = $lhs.gen($program, $scopes).into_val($program, $scopes);
+        let rhs = $rhs.gen($program, $scopes).into_val($program, $scopes);
+        let value = generate_int_op($program, $scopes, ty, $op, lhs, rhs);
+        ExprValue::Int(value)
+    }};
+}
+
+impl<'ast> GenerateProgram<'ast> for BitAndExpr {
+    type Out = ExprValue<'ast>;
+
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        match self {
+            Self::Eq(exp) => exp.gen(program, scopes),
+            Self::BitAndEq(lhs, rhs) => {
+                generate_bitwise_ops!(self, lhs, rhs, program, scopes, BinaryOp::And)
+            }
+        }
+    }
+}
+
+impl<'ast> GenerateProgram<'ast> for BitXorExpr {
+    type Out = ExprValue<'ast>;
+
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        match self {
+            Self::BitAnd(exp) => exp.gen(program, scopes),
+            Self::BitXorBitAnd(lhs, rhs) => {
+                generate_bitwise_ops!(self, lhs, rhs, program, scopes, BinaryOp::Xor)
+            }
+        }
+    }
+}
+
+impl<'ast> GenerateProgram<'ast> for BitOrExpr {
+    type Out = ExprValue<'ast>;
+
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        match self {
+            Self::BitXor(exp) => exp.gen(program, scopes),
+            Self::BitOrBitXor(lhs, rhs) => {
+                generate_bitwise_ops!(self, lhs, rhs, program, scopes, BinaryOp::Or)
             }
         }
     }
@@ -717,11 +1880,8 @@ macro_rules! generate_logical_ops {
         // generate result
         let result = current_fun!($scopes).new_alloc($program, Type::get_i32(), None);
         // generate left-hand side expression
-        let lhs = $lhs.gen($program, $scopes)?.into_int($program, $scopes)?;
+        let lhs = $lhs.gen($program, $scopes).into_val($program, $scopes);
         let info = current_fun_mut!($scopes);
-        let zero = info.new_value($program).integer(0);
-        let lhs = info.new_value($program).binary(BinaryOp::NotEq, lhs, zero);
-        info.push_inst($program, lhs);
         let store = info.new_value($program).store(lhs, result);
         info.push_inst($program, store);
         // generate basic blocks and branch
@@ -731,10 +1891,8 @@ macro_rules! generate_logical_ops {
         info.push_inst($program, br);
         // generate right-hand side expression
         info.push_bb($program, $rhs_bb);
-        let rhs = $rhs.gen($program, $scopes)?.into_int($program, $scopes)?;
+        let rhs = $rhs.gen($program, $scopes).into_val($program, $scopes);
         let info = current_fun_mut!($scopes);
-        let rhs = info.new_value($program).binary(BinaryOp::NotEq, rhs, zero);
-        info.push_inst($program, rhs);
         let store = info.new_value($program).store(rhs, result);
         info.push_inst($program, store);
         // generate jump
@@ -744,17 +1902,17 @@ macro_rules! generate_logical_ops {
         // generate load
         let load = info.new_value($program).load(result);
         info.push_inst($program, load);
-        Ok(ExprValue::Int(load))
+        ExprValue::Bool(load)
     }};
 }
 
 impl<'ast> GenerateProgram<'ast> for LAndExpr {
-    type Out = ExprValue;
+    type Out = ExprValue<'ast>;
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         match self {
-            Self::Eq(exp) => exp.gen(program, scopes),
-            Self::LAndEq(lhs, rhs) => generate_logical_ops! {
+            Self::BitOr(exp) => exp.gen(program, scopes),
+            Self::LAndBitOr(lhs, rhs) => generate_logical_ops! {
               lhs, rhs, program, scopes, "land", rhs_bb, end_bb, rhs_bb, end_bb
             },
         }
@@ -762,9 +1920,9 @@ impl<'ast> GenerateProgram<'ast> for LAndExpr {
 }
 
 impl<'ast> GenerateProgram<'ast> for LOrExpr {
-    type Out = ExprValue;
+    type Out = ExprValue<'ast>;
 
-    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+    fn gen(&'ast self, program: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
         match self {
             Self::LAnd(exp) => exp.gen(program, scopes),
             Self::LOrLAnd(lhs, rhs) => generate_logical_ops! {
@@ -774,57 +1932,336 @@ impl<'ast> GenerateProgram<'ast> for LOrExpr {
     }
 }
 
+/// Returns the fixed-width integer type of the given integer type, `None` for `Int`.
+fn int_ty(ty: &Ty) -> Option<IntTy> {
+    match ty {
+        Ty::Sized(ty) => Some(*ty),
+        _ => None,
+    }
+}
+
+/// Generates a binary operation on integers of the given type (`None` for `Int`),
+/// results of arithmetic operations are truncated to the type.
+fn generate_int_op(
+    program: &mut Program,
+    scopes: &Scopes,
+    ty: Option<IntTy>,
+    op: BinaryOp,
+    mut lhs: IrValue,
+    mut rhs: IrValue,
+) -> IrValue {
+    // shift amounts of type `I64` are truncated
+    if let BinaryOp::Shl | BinaryOp::Sar | BinaryOp::Shr = op {
+        rhs = values::low_word(program, scopes, rhs);
+    }
+    if ty == Some(IntTy::I64) {
+        return generate_long_op(program, scopes, op, lhs, rhs);
+    }
+    let info = current_fun!(scopes);
+    let Some(ty) = ty else {
+        let value = info.new_value(program).binary(op, lhs, rhs);
+        info.push_inst(program, value);
+        return value;
+    };
+    let value = match op {
+        BinaryOp::Div if ty == IntTy::U32 => {
+            return call_intrinsic(program, scopes, Intrinsic::Divu, vec![lhs, rhs])
+        }
+        BinaryOp::Mod if ty == IntTy::U32 => {
+            return call_intrinsic(program, scopes, Intrinsic::Remu, vec![lhs, rhs])
+        }
+        // flipping the sign bits turns signed comparisons into unsigned ones
+        BinaryOp::Lt | BinaryOp::Gt | BinaryOp::Le | BinaryOp::Ge if ty == IntTy::U32 => {
+            let sign = info.new_value(program).integer(i32::MIN);
+            lhs = info.new_value(program).binary(BinaryOp::Xor, lhs, sign);
+            info.push_inst(program, lhs);
+            rhs = info.new_value(program).binary(BinaryOp::Xor, rhs, sign);
+            info.push_inst(program, rhs);
+            info.new_value(program).binary(op, lhs, rhs)
+        }
+        // unsigned values are zero-extended, so `>>` is a logical shift for them,
+        // and `>>>` shifts the bits of narrow signed values without the extension
+        BinaryOp::Sar if ty == IntTy::U32 => {
+            info.new_value(program).binary(BinaryOp::Shr, lhs, rhs)
+        }
+        BinaryOp::Shr if ty.is_signed() && ty.size() < 4 => {
+            let mask = (1 << (8 * ty.size())) - 1;
+            let mask = info.new_value(program).integer(mask);
+            lhs = info.new_value(program).binary(BinaryOp::And, lhs, mask);
+            info.push_inst(program, lhs);
+            info.new_value(program).binary(op, lhs, rhs)
+        }
+        _ => info.new_value(program).binary(op, lhs, rhs),
+    };
+    info.push_inst(program, value);
+    match op {
+        BinaryOp::Add
+        | BinaryOp::Sub
+        | BinaryOp::Mul
+        | BinaryOp::Div
+        | BinaryOp::Shl
+        | BinaryOp::Shr => generate_wrap(program, scopes, value, ty),
+        _ => value,
+    }
+}
+
+/// Generates the truncation of an integer to the given type, values of types
+/// narrower than 32 bits are kept sign- or zero-extended.
+fn generate_wrap(program: &mut Program, scopes: &Scopes, value: IrValue, ty: IntTy) -> IrValue {
+    let info = current_fun!(scopes);
+    if let ValueKind::Integer(num) = program.func(info.fun()).dfg().value(value).kind() {
+        let num = ty.wrap(num.value());
+        return info.new_value(program).integer(num);
+    }
+    let value = match ty {
+        IntTy::U8 => {
+            let mask = info.new_value(program).integer(0xff);
+            info.new_value(program).binary(BinaryOp::And, value, mask)
+        }
+        IntTy::I8 | IntTy::I16 => {
+            let shift = info.new_value(program).integer(32 - 8 * ty.size() as i32);
+            let value = info.new_value(program).binary(BinaryOp::Shl, value, shift);
+            info.push_inst(program, value);
+            info.new_value(program).binary(BinaryOp::Sar, value, shift)
+        }
+        IntTy::U32 | IntTy::I64 => return value,
+    };
+    info.push_inst(program, value);
+    value
+}
+
+/// Generates a binary operation on `I64` values, which is computed from the words
+/// of the operands. Returns a new `I64` value, or a Bool for comparisons.
+fn generate_long_op(
+    program: &mut Program,
+    scopes: &Scopes,
+    op: BinaryOp,
+    lhs: IrValue,
+    rhs: IrValue,
+) -> IrValue {
+    let word_op =
+        |program: &mut Program, op, lhs, rhs| generate_int_op(program, scopes, None, op, lhs, rhs);
+    let (lhs_lo, lhs_hi) = values::words(program, scopes, lhs);
+    if let BinaryOp::Shl | BinaryOp::Sar | BinaryOp::Shr = op {
+        return generate_long_shift(program, scopes, op, lhs_lo, lhs_hi, rhs);
+    }
+    let (rhs_lo, rhs_hi) = values::words(program, scopes, rhs);
+    let (lo, hi) = match op {
+        // the carry (or borrow) of the low words is an unsigned overflow
+        BinaryOp::Add | BinaryOp::Sub => {
+            let lo = word_op(program, op, lhs_lo, rhs_lo);
+            let carry = match op {
+                BinaryOp::Add => generate_ltu(program, scopes, lo, lhs_lo),
+                _ => generate_ltu(program, scopes, lhs_lo, rhs_lo),
+            };
+            let hi = word_op(program, op, lhs_hi, rhs_hi);
+            (lo, word_op(program, op, hi, carry))
+        }
+        // the product of the high words only affects bits above 64
+        BinaryOp::Mul => {
+            let lo = word_op(program, op, lhs_lo, rhs_lo);
+            let hi = call_intrinsic(program, scopes, Intrinsic::Mulhu, vec![lhs_lo, rhs_lo]);
+            let cross = word_op(program, op, lhs_lo, rhs_hi);
+            let hi = word_op(program, BinaryOp::Add, hi, cross);
+            let cross = word_op(program, op, lhs_hi, rhs_lo);
+            (lo, word_op(program, BinaryOp::Add, hi, cross))
+        }
+        BinaryOp::And | BinaryOp::Or | BinaryOp::Xor => (
+            word_op(program, op, lhs_lo, rhs_lo),
+            word_op(program, op, lhs_hi, rhs_hi),
+        ),
+        BinaryOp::Eq | BinaryOp::NotEq => {
+            let lo = word_op(program, BinaryOp::Xor, lhs_lo, rhs_lo);
+            let hi = word_op(program, BinaryOp::Xor, lhs_hi, rhs_hi);
+            let diff = word_op(program, BinaryOp::Or, lo, hi);
+            let zero = current_fun!(scopes).new_value(program).integer(0);
+            return word_op(program, op, diff, zero);
+        }
+        // `a > b` is `b < a`, and `a >= b` is `!(a < b)`
+        BinaryOp::Lt | BinaryOp::Gt | BinaryOp::Le | BinaryOp::Ge => {
+            let (lhs, rhs) = match op {
+                BinaryOp::Lt | BinaryOp::Ge => ((lhs_lo, lhs_hi), (rhs_lo, rhs_hi)),
+                _ => ((rhs_lo, rhs_hi), (lhs_lo, lhs_hi)),
+            };
+            let hi_lt = word_op(program, BinaryOp::Lt, lhs.1, rhs.1);
+            let hi_eq = word_op(program, BinaryOp::Eq, lhs.1, rhs.1);
+            let lo_lt = generate_ltu(program, scopes, lhs.0, rhs.0);
+            let lo_lt = word_op(program, BinaryOp::And, hi_eq, lo_lt);
+            let lt = word_op(program, BinaryOp::Or, hi_lt, lo_lt);
+            if let BinaryOp::Lt | BinaryOp::Gt = op {
+                return lt;
+            }
+            let zero = current_fun!(scopes).new_value(program).integer(0);
+            return word_op(program, BinaryOp::Eq, lt, zero);
+        }
+        // division is rejected by the static checks
+        _ => unreachable!(),
+    };
+    values::new_long(program, scopes, lo, hi)
+}
+
+/// Generates a shift of the `I64` value with the given words, the amount is
+/// taken modulo 64. Both words are shifted by the amount modulo 32, then
+/// the result is selected by masks, by whether the amount is at least 32.
+fn generate_long_shift(
+    program: &mut Program,
+    scopes: &Scopes,
+    op: BinaryOp,
+    lo: IrValue,
+    hi: IrValue,
+    amount: IrValue,
+) -> IrValue {
+    let word_op =
+        |program: &mut Program, op, lhs, rhs| generate_int_op(program, scopes, None, op, lhs, rhs);
+    let int = |program: &mut Program, num| current_fun!(scopes).new_value(program).integer(num);
+    let mask = int(program, 63);
+    let amount = word_op(program, BinaryOp::And, amount, mask);
+    let mask = int(program, 31);
+    let shift = word_op(program, BinaryOp::And, amount, mask);
+    let five = int(program, 5);
+    let is_far = word_op(program, BinaryOp::Shr, amount, five);
+    let one = int(program, 1);
+    let near = word_op(program, BinaryOp::Sub, is_far, one);
+    let zero = int(program, 0);
+    let far = word_op(program, BinaryOp::Sub, zero, is_far);
+    // the bits moved to the other word are shifted by one first,
+    // as shifts by 32 leave words unchanged
+    let thirty_one = int(program, 31);
+    let back = word_op(program, BinaryOp::Sub, thirty_one, shift);
+    let select = |program: &mut Program, near_value, far_value| {
+        let near_value = word_op(program, BinaryOp::And, near_value, near);
+        let far_value = word_op(program, BinaryOp::And, far_value, far);
+        word_op(program, BinaryOp::Or, near_value, far_value)
+    };
+    let (lo, hi) = match op {
+        BinaryOp::Shl => {
+            let moved = word_op(program, BinaryOp::Shr, lo, one);
+            let moved = word_op(program, BinaryOp::Shr, moved, back);
+            let lo = word_op(program, BinaryOp::Shl, lo, shift);
+            let hi = word_op(program, BinaryOp::Shl, hi, shift);
+            let hi = word_op(program, BinaryOp::Or, hi, moved);
+            (select(program, lo, zero), select(program, hi, lo))
+        }
+        // the high word is filled with its sign by `>>`, and with zeros by `>>>`
+        _ => {
+            let moved = word_op(program, BinaryOp::Shl, hi, one);
+            let moved = word_op(program, BinaryOp::Shl, moved, back);
+            let lo = word_op(program, BinaryOp::Shr, lo, shift);
+            let lo = word_op(program, BinaryOp::Or, lo, moved);
+            let hi_shifted = word_op(program, op, hi, shift);
+            let fill = match op {
+                BinaryOp::Sar => word_op(program, BinaryOp::Sar, hi, thirty_one),
+                _ => zero,
+            };
+            (
+                select(program, lo, hi_shifted),
+                select(program, hi_shifted, fill),
+            )
+        }
+    };
+    values::new_long(program, scopes, lo, hi)
+}
+
+/// Generates the unsigned comparison `lhs < rhs` of words.
+fn generate_ltu(program: &mut Program, scopes: &Scopes, lhs: IrValue, rhs: IrValue) -> IrValue {
+    generate_int_op(program, scopes, Some(IntTy::U32), BinaryOp::Lt, lhs, rhs)
+}
+
+/// Generates the conversion of an integer or a Bool of the given type to `I64`,
+/// signed integers are sign-extended, and others are zero-extended.
+fn generate_extend(program: &mut Program, scopes: &Scopes, value: IrValue, from: &Ty) -> IrValue {
+    match from {
+        Ty::Bool | Ty::Sized(IntTy::U8 | IntTy::U32) => {
+            let zero = current_fun!(scopes).new_value(program).integer(0);
+            values::new_long(program, scopes, value, zero)
+        }
+        _ => values::into_long(program, scopes, value),
+    }
+}
+
 impl<'ast> GenerateProgram<'ast> for ConstExpr {
     type Out = i32;
 
-    fn gen(&'ast self, _: &mut Program, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
-        (E0010::E0010 { ast: self }).run(scopes)?
+    fn gen(&'ast self, _: &mut Program, scopes: &mut Scopes<'ast>) -> Self::Out {
+        scopes.types.value(&self.exp)
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for MulOp {
     type Out = BinaryOp;
 
-    fn gen(&'ast self, _: &mut Program, _: &mut Scopes<'ast>) -> Result<Self::Out> {
-        Ok(match self {
+    fn gen(&'ast self, _: &mut Program, _: &mut Scopes<'ast>) -> Self::Out {
+        match self {
             MulOp::Mul(_) => BinaryOp::Mul,
             MulOp::Div(_) => BinaryOp::Div,
             MulOp::Mod(_) => BinaryOp::Mod,
-        })
+        }
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for AddOp {
     type Out = BinaryOp;
 
-    fn gen(&'ast self, _: &mut Program, _: &mut Scopes<'ast>) -> Result<Self::Out> {
-        Ok(match self {
+    fn gen(&'ast self, _: &mut Program, _: &mut Scopes<'ast>) -> Self::Out {
+        match self {
             AddOp::Add(_) => BinaryOp::Add,
             AddOp::Sub(_) => BinaryOp::Sub,
-        })
+        }
+    }
+}
+
+impl<'ast> GenerateProgram<'ast> for ShiftOp {
+    type Out = BinaryOp;
+
+    fn gen(&'ast self, _: &mut Program, _: &mut Scopes<'ast>) -> Self::Out {
+        match self {
+            ShiftOp::Shl(_) => BinaryOp::Shl,
+            ShiftOp::Sar(_) => BinaryOp::Sar,
+            ShiftOp::Shr(_) => BinaryOp::Shr,
+        }
+    }
+}
+
+impl<'ast> GenerateProgram<'ast> for CompoundOp {
+    type Out = BinaryOp;
+
+    fn gen(&'ast self, _: &mut Program, _: &mut Scopes<'ast>) -> Self::Out {
+        match self {
+            CompoundOp::Add(_) => BinaryOp::Add,
+            CompoundOp::Sub(_) => BinaryOp::Sub,
+            CompoundOp::Mul(_) => BinaryOp::Mul,
+            CompoundOp::Div(_) => BinaryOp::Div,
+            CompoundOp::Mod(_) => BinaryOp::Mod,
+            CompoundOp::And(_) => BinaryOp::And,
+            CompoundOp::Or(_) => BinaryOp::Or,
+            CompoundOp::Xor(_) => BinaryOp::Xor,
+            CompoundOp::Shl(_) => BinaryOp::Shl,
+            CompoundOp::Sar(_) => BinaryOp::Sar,
+            CompoundOp::Shr(_) => BinaryOp::Shr,
+        }
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for RelOp {
     type Out = BinaryOp;
 
-    fn gen(&'ast self, _: &mut Program, _: &mut Scopes<'ast>) -> Result<Self::Out> {
-        Ok(match self {
+    fn gen(&'ast self, _: &mut Program, _: &mut Scopes<'ast>) -> Self::Out {
+        match self {
             RelOp::Lt(_) => BinaryOp::Lt,
             RelOp::Gt(_) => BinaryOp::Gt,
             RelOp::Le(_) => BinaryOp::Le,
             RelOp::Ge(_) => BinaryOp::Ge,
-        })
+        }
     }
 }
 
 impl<'ast> GenerateProgram<'ast> for EqOp {
     type Out = BinaryOp;
 
-    fn gen(&'ast self, _: &mut Program, _: &mut Scopes<'ast>) -> Result<Self::Out> {
-        Ok(match self {
+    fn gen(&'ast self, _: &mut Program, _: &mut Scopes<'ast>) -> Self::Out {
+        match self {
             EqOp::Eq(_) => BinaryOp::Eq,
             EqOp::Ne(_) => BinaryOp::NotEq,
-        })
+        }
     }
 }
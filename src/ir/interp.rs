@@ -0,0 +1,431 @@
+//! Interpreter of Koopa IR programs.
+//!
+//! Memory is a flat byte array addressed by `i32` pointers, global
+//! allocations are placed first and local allocations are pushed on top
+//! of them, then popped when the function returns. Calls are handled with
+//! an explicit frame stack, so deep recursion does not overflow the host.
+
+use super::intrinsic::Intrinsic;
+use koopa::ir::entities::ValueData;
+use koopa::ir::values::BinaryOp;
+use koopa::ir::{BasicBlock, Function, Program, Type, TypeKind, Value, ValueKind};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+/// Address of the first allocation, so that null pointers are invalid.
+const MEM_BASE: usize = 4;
+/// Maximum size of the memory in bytes.
+const MEM_LIMIT: usize = 1 << 28;
+/// Maximum depth of nested calls.
+const MAX_DEPTH: usize = 1 << 20;
+
+/// Possible errors at runtime.
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    NoMain,
+    UnknownFunction(String),
+    InvalidAccess(i32),
+    DivByZero,
+    StackOverflow,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            Self::NoMain => write!(f, "function `main` not found"),
+            Self::UnknownFunction(name) => write!(f, "call to unknown function `{}`", name),
+            Self::InvalidAccess(addr) => write!(f, "invalid memory access at {:#x}", addr),
+            Self::DivByZero => write!(f, "attempt to divide by zero"),
+            Self::StackOverflow => write!(f, "stack overflow"),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Result type of the interpreter.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Runs the `main` function of the given program, returns its return value.
+///
+/// The SysY runtime library functions read from `input` and write to `output`.
+pub fn run(program: &Program, input: &mut dyn BufRead, output: &mut dyn Write) -> Result<i32> {
+    Type::set_ptr_size(4);
+    let main = program
+        .func_layout()
+        .iter()
+        .copied()
+        .find(|&f| program.func(f).name() == "@main")
+        .ok_or(Error::NoMain)?;
+    let mut interp = Interpreter::new(program, input, output);
+    interp.init_globals();
+    let ret = interp.run(main);
+    interp.output.flush()?;
+    ret
+}
+
+/// Activation record of a function.
+struct Frame {
+    fun: Function,
+    args: Vec<i32>,
+    values: HashMap<Value, i32>,
+    bb: BasicBlock,
+    pc: usize,
+    /// Size of the memory on entry, local allocations are above it.
+    mem_size: usize,
+    /// The call instruction in the caller that receives the return value.
+    dest: Option<Value>,
+}
+
+struct Interpreter<'p, 'io> {
+    program: &'p Program,
+    /// Instructions of each basic block in each function.
+    insts: HashMap<Function, HashMap<BasicBlock, Vec<Value>>>,
+    globals: HashMap<Value, i32>,
+    mem: Vec<u8>,
+    frames: Vec<Frame>,
+    input: &'io mut dyn BufRead,
+    output: &'io mut dyn Write,
+}
+
+impl<'p, 'io> Interpreter<'p, 'io> {
+    fn new(program: &'p Program, input: &'io mut dyn BufRead, output: &'io mut dyn Write) -> Self {
+        let insts = program
+            .funcs()
+            .iter()
+            .map(|(&fun, data)| {
+                let bbs = data
+                    .layout()
+                    .bbs()
+                    .iter()
+                    .map(|(&bb, node)| (bb, node.insts().keys().copied().collect()))
+                    .collect();
+                (fun, bbs)
+            })
+            .collect();
+        Self {
+            program,
+            insts,
+            globals: HashMap::new(),
+            mem: vec![0; MEM_BASE],
+            frames: Vec::new(),
+            input,
+            output,
+        }
+    }
+
+    /// Allocates and initializes all global allocations.
+    fn init_globals(&mut self) {
+        for &value in self.program.inst_layout() {
+            let data = self.program.borrow_value(value);
+            let ValueKind::GlobalAlloc(alloc) = data.kind() else {
+                continue;
+            };
+            let addr = self.mem.len();
+            self.write_init(alloc.init());
+            self.globals.insert(value, addr as i32);
+        }
+    }
+
+    /// Appends the given global initializer to the memory.
+    fn write_init(&mut self, init: Value) {
+        let data = self.program.borrow_value(init);
+        match data.kind() {
+            ValueKind::Integer(i) => self.mem.extend(i.value().to_le_bytes()),
+            ValueKind::Aggregate(agg) => {
+                for &elem in agg.elems() {
+                    self.write_init(elem);
+                }
+            }
+            _ => {
+                let size = self.mem.len() + data.ty().size();
+                self.mem.resize(size, 0);
+            }
+        }
+    }
+
+    /// Runs the given function until it returns.
+    fn run(&mut self, main: Function) -> Result<i32> {
+        self.enter(main, Vec::new(), None)?;
+        loop {
+            let frame = self.frames.last_mut().unwrap();
+            let inst = self.insts[&frame.fun][&frame.bb][frame.pc];
+            frame.pc += 1;
+            let fun = self.program.func(frame.fun);
+            let data = fun.dfg().value(inst);
+            let result = match data.kind() {
+                ValueKind::Alloc(_) => self.alloc(pointee_size(data.ty()))?,
+                ValueKind::Load(load) => self.load(self.value(load.src()))?,
+                ValueKind::Store(store) => {
+                    let (value, addr) = (self.value(store.value()), self.value(store.dest()));
+                    self.store(addr, value)?;
+                    continue;
+                }
+                ValueKind::GetPtr(ptr) => {
+                    let (src, index) = (self.value(ptr.src()), self.value(ptr.index()));
+                    src.wrapping_add(index.wrapping_mul(pointee_size(data.ty()) as i32))
+                }
+                ValueKind::GetElemPtr(ptr) => {
+                    let (src, index) = (self.value(ptr.src()), self.value(ptr.index()));
+                    src.wrapping_add(index.wrapping_mul(pointee_size(data.ty()) as i32))
+                }
+                ValueKind::Binary(bin) => {
+                    let (lhs, rhs) = (self.value(bin.lhs()), self.value(bin.rhs()));
+                    binary(bin.op(), lhs, rhs)?
+                }
+                ValueKind::Branch(br) => {
+                    let target = if self.value(br.cond()) != 0 {
+                        br.true_bb()
+                    } else {
+                        br.false_bb()
+                    };
+                    self.jump(target);
+                    continue;
+                }
+                ValueKind::Jump(jump) => {
+                    self.jump(jump.target());
+                    continue;
+                }
+                ValueKind::Call(call) => {
+                    let args: Vec<_> = call.args().iter().map(|&arg| self.value(arg)).collect();
+                    let callee = self.program.func(call.callee());
+                    if callee.layout().entry_bb().is_some() {
+                        self.enter(call.callee(), args, Some(inst))?;
+                        continue;
+                    }
+                    self.call_runtime(&callee.name()[1..], &args)?
+                }
+                ValueKind::Return(ret) => {
+                    let value = ret.value().map_or(0, |v| self.value(v));
+                    let frame = self.frames.pop().unwrap();
+                    self.mem.truncate(frame.mem_size);
+                    match self.frames.last_mut() {
+                        Some(caller) => {
+                            caller.values.insert(frame.dest.unwrap(), value);
+                            continue;
+                        }
+                        None => return Ok(value),
+                    }
+                }
+                _ => unreachable!(),
+            };
+            self.frames.last_mut().unwrap().values.insert(inst, result);
+        }
+    }
+
+    /// Pushes a new frame for calling the given function.
+    fn enter(&mut self, fun: Function, args: Vec<i32>, dest: Option<Value>) -> Result<()> {
+        if self.frames.len() >= MAX_DEPTH {
+            return Err(Error::StackOverflow);
+        }
+        let entry = self.program.func(fun).layout().entry_bb().unwrap();
+        self.frames.push(Frame {
+            fun,
+            args,
+            values: HashMap::new(),
+            bb: entry,
+            pc: 0,
+            mem_size: self.mem.len(),
+            dest,
+        });
+        Ok(())
+    }
+
+    /// Continues the execution of the current function at the given basic block.
+    fn jump(&mut self, bb: BasicBlock) {
+        let frame = self.frames.last_mut().unwrap();
+        frame.bb = bb;
+        frame.pc = 0;
+    }
+
+    /// Returns the runtime value of the given IR value in the current frame.
+    fn value(&self, value: Value) -> i32 {
+        if value.is_global() {
+            return self.globals[&value];
+        }
+        let frame = self.frames.last().unwrap();
+        let data: &ValueData = self.program.func(frame.fun).dfg().value(value);
+        match data.kind() {
+            ValueKind::Integer(i) => i.value(),
+            ValueKind::ZeroInit(_) | ValueKind::Undef(_) => 0,
+            ValueKind::FuncArgRef(arg) => frame.args[arg.index()],
+            _ => frame.values[&value],
+        }
+    }
+
+    /// Allocates zeroed memory of the given size, returns its address.
+    fn alloc(&mut self, size: usize) -> Result<i32> {
+        let addr = self.mem.len();
+        if addr + size > MEM_LIMIT {
+            return Err(Error::StackOverflow);
+        }
+        self.mem.resize(addr + size, 0);
+        Ok(addr as i32)
+    }
+
+    /// Returns the given number of bytes of memory at the given address.
+    fn bytes(&mut self, addr: i32, len: usize) -> Result<&mut [u8]> {
+        let start = addr as usize;
+        if addr < MEM_BASE as i32 || start + len > self.mem.len() {
+            return Err(Error::InvalidAccess(addr));
+        }
+        Ok(&mut self.mem[start..start + len])
+    }
+
+    fn load(&mut self, addr: i32) -> Result<i32> {
+        Ok(i32::from_le_bytes(self.bytes(addr, 4)?.try_into().unwrap()))
+    }
+
+    fn store(&mut self, addr: i32, value: i32) -> Result<()> {
+        self.bytes(addr, 4)?.copy_from_slice(&value.to_le_bytes());
+        Ok(())
+    }
+
+    /// Calls an intrinsic function.
+    fn call_intrinsic(&mut self, intrinsic: Intrinsic, args: &[i32]) -> Result<i32> {
+        Ok(match (intrinsic, args) {
+            (Intrinsic::Lb, &[addr]) => self.bytes(addr, 1)?[0] as i8 as i32,
+            (Intrinsic::Lbu, &[addr]) => self.bytes(addr, 1)?[0] as i32,
+            (Intrinsic::Lh, &[addr]) => {
+                i16::from_le_bytes(self.bytes(addr, 2)?.try_into().unwrap()) as i32
+            }
+            (Intrinsic::Lhu, &[addr]) => {
+                u16::from_le_bytes(self.bytes(addr, 2)?.try_into().unwrap()) as i32
+            }
+            (Intrinsic::Sb, &[value, addr]) => {
+                self.bytes(addr, 1)?
+                    .copy_from_slice(&value.to_le_bytes()[..1]);
+                0
+            }
+            (Intrinsic::Sh, &[value, addr]) => {
+                self.bytes(addr, 2)?
+                    .copy_from_slice(&value.to_le_bytes()[..2]);
+                0
+            }
+            (Intrinsic::Divu | Intrinsic::Remu, &[_, 0]) => return Err(Error::DivByZero),
+            (Intrinsic::Divu, &[lhs, rhs]) => (lhs as u32 / rhs as u32) as i32,
+            (Intrinsic::Remu, &[lhs, rhs]) => (lhs as u32 % rhs as u32) as i32,
+            (Intrinsic::Mulhu, &[lhs, rhs]) => {
+                ((lhs as u32 as u64 * rhs as u32 as u64) >> 32) as i32
+            }
+            (Intrinsic::Offset, &[ptr, offset]) => ptr.wrapping_add(offset),
+            (Intrinsic::Cast(_) | Intrinsic::Addr(_), &[ptr]) => ptr,
+            _ => unreachable!(),
+        })
+    }
+
+    /// Calls a function of the SysY runtime library or an intrinsic.
+    fn call_runtime(&mut self, name: &str, args: &[i32]) -> Result<i32> {
+        if let Some(intrinsic) = Intrinsic::from_name(name) {
+            return self.call_intrinsic(intrinsic, args);
+        }
+        match (name, args) {
+            ("getint", []) => return self.read_int(),
+            ("getch", []) => return Ok(self.read_byte()?.map_or(-1, i32::from)),
+            ("getarray", &[addr]) => {
+                let len = self.read_int()?;
+                for i in 0..len {
+                    let value = self.read_int()?;
+                    self.store(addr.wrapping_add(4 * i), value)?;
+                }
+                return Ok(len);
+            }
+            ("putint", &[value]) => write!(self.output, "{}", value)?,
+            ("putch", &[c]) => self.output.write_all(&[c as u8])?,
+            ("putarray", &[len, addr]) => {
+                write!(self.output, "{}:", len)?;
+                for i in 0..len {
+                    let value = self.load(addr.wrapping_add(4 * i))?;
+                    write!(self.output, " {}", value)?;
+                }
+                writeln!(self.output)?;
+            }
+            ("putstr", &[addr]) => {
+                let mut i = 0;
+                loop {
+                    let c = self.load(addr.wrapping_add(4 * i))?;
+                    if c == 0 {
+                        break;
+                    }
+                    self.output.write_all(&[c as u8])?;
+                    i += 1;
+                }
+            }
+            ("starttime" | "stoptime", _) => {}
+            _ => return Err(Error::UnknownFunction(name.to_string())),
+        }
+        Ok(0)
+    }
+
+    /// Reads a byte from the input, returns `None` at the end of input.
+    fn read_byte(&mut self) -> Result<Option<u8>> {
+        let byte = self.peek_byte()?;
+        if byte.is_some() {
+            self.input.consume(1);
+        }
+        Ok(byte)
+    }
+
+    fn peek_byte(&mut self) -> Result<Option<u8>> {
+        Ok(self.input.fill_buf()?.first().copied())
+    }
+
+    /// Reads a decimal integer from the input, returns 0 if there is none.
+    fn read_int(&mut self) -> Result<i32> {
+        while self.peek_byte()?.is_some_and(|c| c.is_ascii_whitespace()) {
+            self.input.consume(1);
+        }
+        let negative = self.peek_byte()? == Some(b'-');
+        if negative {
+            self.input.consume(1);
+        }
+        let mut value = 0i32;
+        while let Some(c) = self.peek_byte()?.filter(u8::is_ascii_digit) {
+            value = value.wrapping_mul(10).wrapping_add((c - b'0') as i32);
+            self.input.consume(1);
+        }
+        Ok(if negative {
+            value.wrapping_neg()
+        } else {
+            value
+        })
+    }
+}
+
+/// Returns the size of the type the given pointer type points to.
+fn pointee_size(ty: &Type) -> usize {
+    match ty.kind() {
+        TypeKind::Pointer(base) => base.size(),
+        _ => unreachable!(),
+    }
+}
+
+fn binary(op: BinaryOp, lhs: i32, rhs: i32) -> Result<i32> {
+    Ok(match op {
+        BinaryOp::NotEq => (lhs != rhs) as i32,
+        BinaryOp::Eq => (lhs == rhs) as i32,
+        BinaryOp::Gt => (lhs > rhs) as i32,
+        BinaryOp::Lt => (lhs < rhs) as i32,
+        BinaryOp::Ge => (lhs >= rhs) as i32,
+        BinaryOp::Le => (lhs <= rhs) as i32,
+        BinaryOp::Add => lhs.wrapping_add(rhs),
+        BinaryOp::Sub => lhs.wrapping_sub(rhs),
+        BinaryOp::Mul => lhs.wrapping_mul(rhs),
+        BinaryOp::Div | BinaryOp::Mod if rhs == 0 => return Err(Error::DivByZero),
+        BinaryOp::Div => lhs.wrapping_div(rhs),
+        BinaryOp::Mod => lhs.wrapping_rem(rhs),
+        BinaryOp::And => lhs & rhs,
+        BinaryOp::Or => lhs | rhs,
+        BinaryOp::Xor => lhs ^ rhs,
+        BinaryOp::Shl => lhs.wrapping_shl(rhs as u32),
+        BinaryOp::Shr => (lhs as u32).wrapping_shr(rhs as u32) as i32,
+        BinaryOp::Sar => lhs.wrapping_shr(rhs as u32),
+    })
+}
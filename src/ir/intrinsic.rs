@@ -0,0 +1,136 @@
+//! Intrinsic functions.
+//!
+//! Koopa IR only has 32-bit integers and signed operations, so byte and
+//! halfword memory accesses, unsigned division and the high word of unsigned
+//! multiplication are declared as functions, as are conversions of pointers.
+//! The backend generates calls to them as single instructions, and the
+//! interpreter evaluates them directly.
+
+use crate::ast::IntTy;
+use koopa::ir::Type;
+
+/// An intrinsic function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Intrinsic {
+    /// `lb(ptr)`, loads a sign-extended byte.
+    Lb,
+    /// `lbu(ptr)`, loads a zero-extended byte.
+    Lbu,
+    /// `lh(ptr)`, loads a sign-extended halfword.
+    Lh,
+    /// `lhu(ptr)`, loads a zero-extended halfword.
+    Lhu,
+    /// `sb(value, ptr)`, stores the low byte of the value.
+    Sb,
+    /// `sh(value, ptr)`, stores the low halfword of the value.
+    Sh,
+    /// `divu(lhs, rhs)`, unsigned division.
+    Divu,
+    /// `remu(lhs, rhs)`, unsigned remainder.
+    Remu,
+    /// `mulhu(lhs, rhs)`, the high word of the unsigned product.
+    Mulhu,
+    /// `offset(ptr, bytes)`, adds a byte offset to the pointer.
+    Offset,
+    /// `cast(ptr)`, converts a pointer to a word of a struct into a pointer
+    /// with the given number of `*`, for pointers stored in struct fields.
+    Cast(usize),
+    /// `addr(ptr)`, converts a pointer with the given number of `*`
+    /// into its address, for comparisons of pointers.
+    Addr(usize),
+}
+
+impl Intrinsic {
+    /// All intrinsics with a fixed signature,
+    /// casts and address conversions are declared when they are used.
+    pub const ALL: [Self; 10] = [
+        Self::Lb,
+        Self::Lbu,
+        Self::Lh,
+        Self::Lhu,
+        Self::Sb,
+        Self::Sh,
+        Self::Divu,
+        Self::Remu,
+        Self::Mulhu,
+        Self::Offset,
+    ];
+
+    /// Returns the intrinsic that loads a value of the given type,
+    /// which must be narrower than a word.
+    pub fn load(ty: IntTy) -> Self {
+        match ty {
+            IntTy::I8 => Self::Lb,
+            IntTy::U8 => Self::Lbu,
+            IntTy::I16 => Self::Lh,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the intrinsic that stores a value of the given type,
+    /// which must be narrower than a word.
+    pub fn store(ty: IntTy) -> Self {
+        match ty.size() {
+            1 => Self::Sb,
+            2 => Self::Sh,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the name of the intrinsic, without the `@` prefix.
+    pub fn name(self) -> String {
+        let name = match self {
+            Self::Lb => "__ophelia_lb",
+            Self::Lbu => "__ophelia_lbu",
+            Self::Lh => "__ophelia_lh",
+            Self::Lhu => "__ophelia_lhu",
+            Self::Sb => "__ophelia_sb",
+            Self::Sh => "__ophelia_sh",
+            Self::Divu => "__ophelia_divu",
+            Self::Remu => "__ophelia_remu",
+            Self::Mulhu => "__ophelia_mulhu",
+            Self::Offset => "__ophelia_offset",
+            Self::Cast(depth) => return format!("__ophelia_cast{}", depth),
+            Self::Addr(depth) => return format!("__ophelia_addr{}", depth),
+        };
+        String::from(name)
+    }
+
+    /// Returns the intrinsic with the given name, without the `@` prefix.
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some(depth) = name.strip_prefix("__ophelia_cast") {
+            return depth.parse().ok().map(Self::Cast);
+        }
+        if let Some(depth) = name.strip_prefix("__ophelia_addr") {
+            return depth.parse().ok().map(Self::Addr);
+        }
+        Self::ALL.into_iter().find(|i| i.name() == name)
+    }
+
+    /// Returns the parameter types of the intrinsic.
+    pub fn params_ty(self) -> Vec<Type> {
+        let ptr = Type::get_pointer(Type::get_i32());
+        match self {
+            Self::Lb | Self::Lbu | Self::Lh | Self::Lhu | Self::Cast(_) => vec![ptr],
+            Self::Sb | Self::Sh => vec![Type::get_i32(), ptr],
+            Self::Divu | Self::Remu | Self::Mulhu => vec![Type::get_i32(), Type::get_i32()],
+            Self::Offset => vec![ptr, Type::get_i32()],
+            Self::Addr(depth) => vec![pointer_ty(depth)],
+        }
+    }
+
+    /// Returns the return type of the intrinsic.
+    pub fn ret_ty(self) -> Type {
+        match self {
+            Self::Sb | Self::Sh => Type::get_unit(),
+            Self::Offset => Type::get_pointer(Type::get_i32()),
+            Self::Cast(depth) => pointer_ty(depth),
+            _ => Type::get_i32(),
+        }
+    }
+}
+
+/// Returns the type of pointers to `i32` with the given number of `*`.
+fn pointer_ty(depth: usize) -> Type {
+    (0..depth).fold(Type::get_i32(), |ty, _| Type::get_pointer(ty))
+}
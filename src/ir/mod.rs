@@ -1,37 +1,68 @@
-mod eval;
 mod fun;
 mod gen;
+pub mod interp;
+pub mod intrinsic;
 mod scopes;
 mod values;
-pub mod error;
 
 use crate::ast::{CompUnit, ConstExpr};
-use eval::Evaluate;
+use crate::sema::Types;
 use gen::GenerateProgram;
-use koopa::ir::{Program, Type};
+use koopa::ir::{Program, Type, ValueKind};
 use scopes::Scopes;
-use std::fmt;
+use std::collections::HashSet;
 
-pub fn gen(comp_unit: &CompUnit) -> Result<Program> {
+/// Generates Koopa IR for the AST, which must have passed [`sema::check`]
+/// with no diagnostics.
+///
+/// [`sema::check`]: crate::sema::check
+pub fn gen<'ast>(comp_unit: &'ast CompUnit, types: &'ast Types<'ast>) -> Program {
     let mut program = Program::new();
-    comp_unit.gen(&mut program, &mut Scopes::new())?;
-    Ok(program)
+    let mut scopes = Scopes::new(types);
+    comp_unit.gen(&mut program, &mut scopes);
+    remove_unused_intrinsics(&mut program, &scopes);
+    program
 }
 
+/// Removes the declarations of intrinsics that are never called.
+fn remove_unused_intrinsics(program: &mut Program, scopes: &Scopes) {
+    let called: HashSet<_> = program
+        .funcs()
+        .values()
+        .flat_map(|data| data.dfg().values().values())
+        .filter_map(|value| match value.kind() {
+            ValueKind::Call(call) => Some(call.callee()),
+            _ => None,
+        })
+        .collect();
+    for fun in scopes.intrinsics.values() {
+        if !called.contains(fun) {
+            program.remove_func(*fun);
+        }
+    }
+}
 
 /// Helper trait for converting dimentions to type.
 pub(crate) trait DimsToType {
-    fn to_type(&self, scopes: &Scopes) -> Result<Type>;
+    /// Returns the lengths of the dimensions.
+    fn to_lens(&self, scopes: &Scopes) -> Vec<usize>;
+
+    /// Returns the array type of the given element type.
+    fn to_array(&self, base: Type, scopes: &Scopes) -> Type {
+        let lens = self.to_lens(scopes);
+        lens.into_iter().rev().fold(base, Type::get_array)
+    }
+
+    /// Returns the integer array type.
+    fn to_type(&self, scopes: &Scopes) -> Type {
+        self.to_array(Type::get_i32(), scopes)
+    }
 }
 
 impl DimsToType for Vec<ConstExpr> {
-    fn to_type(&self, scopes: &Scopes) -> Result<Type> {
-        self.iter().rev().fold(Ok(Type::get_i32()), |b, exp| {
-            let base = b?;
-            let len = exp.eval(scopes).ok_or(Error::FailedToEval)?;
-            (len >= 1)
-                .then(|| Type::get_array(base, len as usize))
-                .ok_or(Error::InvalidArrayLen)
-        })
+    fn to_lens(&self, scopes: &Scopes) -> Vec<usize> {
+        self.iter()
+            .map(|exp| scopes.types.value(&exp.exp) as usize)
+            .collect()
     }
 }
@@ -1,14 +1,31 @@
 use super::fun::FunctionInfo;
+use super::intrinsic::Intrinsic;
 use super::values::Value;
+use crate::ast;
+use crate::sema::{StructInfo, Types};
 use koopa::ir::Value as IrValue;
 use koopa::ir::{BasicBlock, Function, Program, Type};
 use std::collections::HashMap;
 
 pub struct Scopes<'ast> {
-    vals: Vec<HashMap<&'ast str, Value>>,
+    /// Types and constant values from the static checks.
+    pub types: &'ast Types<'ast>,
+    vals: Vec<HashMap<&'ast str, Value<'ast>>>,
     funs: HashMap<&'ast str, Function>,
     pub current_fun: Option<FunctionInfo>,
-    pub loop_info: Vec<(BasicBlock, BasicBlock)>,
+    pub loop_info: Vec<LoopInfo<'ast>>,
+    /// Number of string literals generated so far.
+    pub str_count: usize,
+    /// Declarations of intrinsics.
+    pub intrinsics: HashMap<Intrinsic, Function>,
+}
+
+/// Basic blocks that `break` and `continue` in a loop jump to.
+#[derive(Clone, Copy)]
+pub struct LoopInfo<'ast> {
+    pub label: Option<&'ast ast::Label>,
+    pub break_bb: BasicBlock,
+    pub continue_bb: BasicBlock,
 }
 
 /// Returns a reference to the current funtion information.
@@ -28,13 +45,28 @@ macro_rules! current_fun_mut {
 pub(crate) use current_fun_mut;
 
 impl<'ast> Scopes<'ast> {
-    /// Creates a new `Scopes`.
-    pub fn new() -> Self {
+    /// Creates a new `Scopes` with the results of the static checks.
+    pub fn new(types: &'ast Types<'ast>) -> Self {
         Self {
+            types,
             vals: vec![HashMap::new()],
             funs: HashMap::new(),
             current_fun: None,
             loop_info: Vec::new(),
+            str_count: 0,
+            intrinsics: HashMap::new(),
+        }
+    }
+
+    /// Returns the innermost loop, or the loop with the given label.
+    pub fn loop_info(&self, label: Option<&str>) -> Option<&LoopInfo<'ast>> {
+        match label {
+            Some(name) => self
+                .loop_info
+                .iter()
+                .rev()
+                .find(|l| l.label.is_some_and(|l| l.name == name)),
+            None => self.loop_info.last(),
         }
     }
 
@@ -44,42 +76,32 @@ impl<'ast> Scopes<'ast> {
     }
 
     /// Inserts a new value to the current scope.
-    pub fn new_value(&mut self, id: &'ast str, value: Value) -> Result<()> {
-        let is_global = self.is_global();
-        let cur = self.vals.last_mut().unwrap();
-        if cur.contains_key(id) || (is_global && self.funs.contains_key(id)) {
-            Err(Error::DuplicatedDef)
-        } else {
-            cur.insert(id, value);
-            Ok(())
-        }
+    pub fn new_value(&mut self, id: &'ast str, value: Value<'ast>) {
+        self.vals.last_mut().unwrap().insert(id, value);
     }
 
     /// Returns the value by the given identifier.
-    pub fn value(&self, id: &str) -> Result<&Value> {
-        let mut cur = self.vals.len() as i32 - 1;
-        while cur >= 0 {
-            if let Some(value) = self.vals[cur as usize].get(id) {
-                return Ok(value);
-            }
-            cur -= 1;
-        }
-        Err(Error::SymbolNotFound)
+    pub fn value(&self, id: &str) -> &Value<'ast> {
+        self.vals
+            .iter()
+            .rev()
+            .find_map(|vals| vals.get(id))
+            .unwrap()
     }
 
     /// Inserts a new funtion to the current scope.
-    pub fn new_fun(&mut self, id: &'ast str, fun: Function) -> Result<()> {
-        if self.funs.contains_key(id) || self.vals.first().unwrap().contains_key(id) {
-            Err(Error::DuplicatedDef)
-        } else {
-            self.funs.insert(id, fun);
-            Ok(())
-        }
+    pub fn new_fun(&mut self, id: &'ast str, fun: Function) {
+        self.funs.insert(id, fun);
     }
 
     /// Returns the funtion by the given identifier.
-    pub fn fun(&self, id: &str) -> Result<Function> {
-        self.funs.get(id).copied().ok_or(Error::SymbolNotFound)
+    pub fn fun(&self, id: &str) -> Function {
+        self.funs[id]
+    }
+
+    /// Returns the layout of the struct type by the given identifier.
+    pub fn struct_info(&self, id: &str) -> &'ast StructInfo<'ast> {
+        self.types.struct_info(id).unwrap()
     }
 
     /// Enters a new scope.
@@ -1,15 +1,50 @@
+use super::intrinsic::Intrinsic;
 use super::scopes::{current_fun, Scopes};
+use crate::ast::IntTy;
+use crate::sema::{StructInfo, Ty};
 use koopa::ir::builder_traits::*;
 use koopa::ir::Value as IrValue;
-use koopa::ir::{Program, Type, TypeKind};
+use koopa::ir::{BinaryOp, Program, Type, TypeKind, ValueKind};
 use std::iter::repeat_with;
 
+impl Ty<'_> {
+    /// Returns the Koopa IR type of a pointer to a value of this type,
+    /// pointers to structs point to their first word, and pointers to
+    /// fixed-width integers narrower than a word are byte addresses.
+    pub fn pointer_to(&self) -> Type {
+        Type::get_pointer(match self {
+            Self::Int | Self::Bool | Self::Sized(_) | Self::Struct(_) => Type::get_i32(),
+            Self::Pointer(ty) => ty.pointer_to(),
+            Self::Error | Self::Unit | Self::Array(..) => unreachable!(),
+        })
+    }
+}
+
+impl StructInfo<'_> {
+    /// Returns the Koopa IR type the struct is lowered to, an array of words.
+    pub fn ty(&self) -> Type {
+        Type::get_array(Type::get_i32(), self.size)
+    }
+}
+
 /// A value.
-pub enum Value {
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone)]
+pub enum Value<'ast> {
     /// Koopa IR value.
     Value(IrValue),
     /// Constant integer.
     Const(i32),
+    /// Bool variable.
+    Bool(IrValue),
+    /// Fixed-width integer variable (or array) of the given type,
+    /// with the lengths of the dimensions.
+    Sized(IrValue, IntTy, Vec<usize>),
+    /// Struct variable (or array of structs) of the given struct type.
+    Struct(IrValue, &'ast str),
+    /// Pointer variable (or array), which points to values of the given type,
+    /// with the lengths of the dimensions.
+    Pointer(IrValue, Ty<'ast>, Vec<usize>),
 }
 
 /// An initializer.
@@ -22,7 +57,7 @@ pub enum Initializer {
 impl Initializer {
     /// Reshapes the current initializer by using the given type.
     /// Returns the reshaped initializer.
-    pub fn reshape(self, mut ty: &Type) -> Result<Self> {
+    pub fn reshape(self, mut ty: &Type) -> Self {
         // get length list
         // array `int a[2][3][4]` yields [(4, 4), (3, 12), (2, 24)]
         let mut lens = Vec::new();
@@ -47,32 +82,26 @@ impl Initializer {
             .collect();
         // perform reshape
         match self {
-            v @ (Self::Const(_) | Self::Value(_)) if lens.is_empty() => Ok(v),
+            v @ (Self::Const(_) | Self::Value(_)) if lens.is_empty() => v,
             Self::List(l) if !lens.is_empty() => Self::reshape_impl(l, &lens),
-            _ => Err(Error::InvalidInit),
+            _ => unreachable!(),
         }
     }
 
-    fn reshape_impl(inits: Vec<Self>, lens: &[(usize, usize)]) -> Result<Self> {
+    fn reshape_impl(inits: Vec<Self>, lens: &[(usize, usize)]) -> Self {
         let mut reshaped: Vec<Vec<Self>> = repeat_with(Vec::new).take(lens.len() + 1).collect();
         let mut len = 0;
         // handle initializer elements
         for init in inits {
-            // too many elements
-            if len >= lens.last().unwrap().1 {
-                return Err(Error::InvalidInit);
-            }
             match init {
                 Self::List(list) => {
                     // get the next-level length list
                     let next_lens = match reshaped.iter().position(|v| !v.is_empty()) {
-                        // not aligned
-                        Some(0) => return Err(Error::InvalidInit),
                         Some(i) => &lens[..i],
                         None => &lens[..lens.len() - 1],
                     };
                     // reshape, and add to reshaped initializer list
-                    reshaped[next_lens.len()].push(Self::reshape_impl(list, next_lens)?);
+                    reshaped[next_lens.len()].push(Self::reshape_impl(list, next_lens));
                     Self::carry(&mut reshaped, lens);
                     len += next_lens.last().unwrap().1;
                 }
@@ -90,7 +119,7 @@ impl Initializer {
             Self::carry(&mut reshaped, lens);
             len += 1;
         }
-        Ok(reshaped.pop().unwrap().pop().unwrap())
+        reshaped.pop().unwrap().pop().unwrap()
     }
 
     fn carry(reshaped: &mut [Vec<Self>], lens: &[(usize, usize)]) {
@@ -103,25 +132,36 @@ impl Initializer {
         }
     }
 
+    /// Converts the initializer (must be reshaped first)
+    /// into the list of its elements.
+    pub fn into_elems(self) -> Vec<Self> {
+        match self {
+            Self::List(list) => list.into_iter().flat_map(Self::into_elems).collect(),
+            init => vec![init],
+        }
+    }
+
     /// Converts the initializer (must be reshaped first) into a constant.
-    pub fn into_const(self, program: &mut Program, scopes: &Scopes) -> Result<IrValue> {
+    pub fn into_const(self, program: &mut Program, scopes: &Scopes) -> IrValue {
         match self {
-            Self::Const(num) => Ok(if scopes.is_global() {
-                program.new_value().integer(num)
-            } else {
-                current_fun!(scopes).new_value(program).integer(num)
-            }),
-            Self::Value(_) => Err(Error::FailedToEval),
+            Self::Const(num) => {
+                if scopes.is_global() {
+                    program.new_value().integer(num)
+                } else {
+                    current_fun!(scopes).new_value(program).integer(num)
+                }
+            }
+            Self::Value(_) => unreachable!(),
             Self::List(list) => {
                 let values = list
                     .into_iter()
                     .map(|i| i.into_const(program, scopes))
-                    .collect::<Result<_>>()?;
-                Ok(if scopes.is_global() {
+                    .collect();
+                if scopes.is_global() {
                     program.new_value().aggregate(values)
                 } else {
                     current_fun!(scopes).new_value(program).aggregate(values)
-                })
+                }
             }
         }
     }
@@ -151,47 +191,280 @@ impl Initializer {
 }
 
 /// An expression value.
-pub enum ExprValue {
+pub enum ExprValue<'ast> {
     /// An `void`.
     Void,
-    /// An integer.
+    /// An integer of any integer type, fixed-width integers narrower
+    /// than 32 bits are sign- or zero-extended. `I64` values are pointers
+    /// to temporaries of two words, see [`new_long`].
     Int(IrValue),
     /// An integer pointer.
     IntPtr(IrValue),
+    /// A Bool, which is `0` or `1`.
+    Bool(IrValue),
+    /// A Bool pointer.
+    BoolPtr(IrValue),
+    /// A fixed-width integer pointer.
+    SizedPtr(IrValue, IntTy),
     /// An array pointer (part of array).
     ArrPtr(IrValue),
+    /// A pointer to the first word of a struct of the given type.
+    StructPtr(IrValue, &'ast str),
+    /// A pointer to a value of the given type.
+    Ptr(IrValue, Ty<'ast>),
+    /// A pointer to a pointer to a value of the given type.
+    PtrPtr(IrValue, Ty<'ast>),
 }
 
-impl ExprValue {
+impl<'ast> ExprValue<'ast> {
     /// Converts the value into a right value.
-    pub fn into_val(self, program: &mut Program, scopes: &Scopes) -> Result<IrValue> {
+    pub fn into_val(self, program: &mut Program, scopes: &Scopes) -> IrValue {
         match self {
-            Self::Void => Err(Error::UseVoidValue),
-            Self::Int(val) => Ok(val),
-            Self::IntPtr(ptr) => {
+            Self::Void => unreachable!(),
+            Self::Int(val) | Self::Bool(val) | Self::Ptr(val, _) => val,
+            Self::SizedPtr(ptr, ty) => load(program, scopes, ptr, &Ty::Sized(ty)),
+            Self::IntPtr(ptr) | Self::BoolPtr(ptr) | Self::PtrPtr(ptr, _) => {
                 let info = current_fun!(scopes);
                 let load = info.new_value(program).load(ptr);
                 info.push_inst(program, load);
-                Ok(load)
+                load
             }
-            Self::ArrPtr(ptr) => Ok(ptr),
+            Self::ArrPtr(ptr) | Self::StructPtr(ptr, _) => ptr,
         }
     }
 
-    /// Converts the value into a integer right value.
-    pub fn into_int(self, program: &mut Program, scopes: &Scopes) -> Result<IrValue> {
+    /// Converts the integer value into a word, `I64` values are truncated.
+    pub fn into_word(self, program: &mut Program, scopes: &Scopes) -> IrValue {
+        let value = self.into_val(program, scopes);
+        low_word(program, scopes, value)
+    }
+
+    /// Converts the left value into a right value,
+    /// other values are returned unchanged.
+    pub fn into_rvalue(self, program: &mut Program, scopes: &Scopes) -> Self {
         match self {
-            Self::ArrPtr(_) => Err(Error::NonIntCalc),
-            _ => self.into_val(program, scopes),
+            Self::IntPtr(_) => Self::Int(self.into_val(program, scopes)),
+            Self::BoolPtr(_) => Self::Bool(self.into_val(program, scopes)),
+            Self::SizedPtr(..) => Self::Int(self.into_val(program, scopes)),
+            Self::PtrPtr(_, ref ty) => {
+                let ty = ty.clone();
+                Self::Ptr(self.into_val(program, scopes), ty)
+            }
+            _ => self,
+        }
+    }
+
+    /// Converts the value into a pointer right value and the type of the value
+    /// it points to, integer arrays are converted to pointers to integers.
+    pub fn into_pointer(self, program: &mut Program, scopes: &Scopes) -> (IrValue, Ty<'ast>) {
+        match self {
+            Self::Ptr(ptr, ty) => (ptr, ty),
+            Self::PtrPtr(_, ref ty) => {
+                let ty = ty.clone();
+                (self.into_val(program, scopes), ty)
+            }
+            Self::ArrPtr(ptr) => (ptr, Ty::Int),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Dereferences the pointer, returns the left value it points to.
+    pub fn deref(self, program: &mut Program, scopes: &Scopes) -> Self {
+        let (ptr, ty) = self.into_pointer(program, scopes);
+        match ty {
+            Ty::Int => Self::IntPtr(ptr),
+            Ty::Bool => Self::BoolPtr(ptr),
+            Ty::Sized(ty) => Self::SizedPtr(ptr, ty),
+            Ty::Struct(name) => Self::StructPtr(ptr, name),
+            Ty::Pointer(ty) => Self::PtrPtr(ptr, *ty),
+            Ty::Error | Ty::Unit | Ty::Array(..) => unreachable!(),
+        }
+    }
+
+    /// Returns a pointer to the left value.
+    pub fn into_addr(self, program: &mut Program, scopes: &Scopes) -> Self {
+        let (mut ptr, ty) = match self {
+            Self::IntPtr(ptr) => (ptr, Ty::Int),
+            Self::BoolPtr(ptr) => (ptr, Ty::Bool),
+            Self::SizedPtr(ptr, ty) => (ptr, Ty::Sized(ty)),
+            Self::StructPtr(ptr, name) => (ptr, Ty::Struct(name)),
+            Self::PtrPtr(ptr, ty) => (ptr, Ty::Pointer(Box::new(ty))),
+            _ => unreachable!(),
+        };
+        // variables can only be used as the source or destination of memory
+        // accesses, so their addresses are calculated like array elements
+        let info = current_fun!(scopes);
+        let is_var = ptr.is_global()
+            || matches!(
+                program.func(info.fun()).dfg().value(ptr).kind(),
+                ValueKind::Alloc(_)
+            );
+        if is_var {
+            let zero = info.new_value(program).integer(0);
+            ptr = info.new_value(program).get_ptr(ptr, zero);
+            info.push_inst(program, ptr);
+        }
+        Self::Ptr(ptr, ty)
+    }
+
+    /// Creates a right value of the given type.
+    pub fn from_ty(value: IrValue, ty: &Ty<'ast>) -> Self {
+        match ty {
+            Ty::Unit => Self::Void,
+            Ty::Bool => Self::Bool(value),
+            Ty::Pointer(ty) => Self::Ptr(value, (**ty).clone()),
+            _ => Self::Int(value),
         }
     }
 
     /// Converts the value into a left-value pointer.
-    pub fn into_ptr(self) -> Result<IrValue> {
+    pub fn into_ptr(self) -> IrValue {
         match self {
-            Self::IntPtr(ptr) => Ok(ptr),
-            Self::ArrPtr(_) => Err(Error::ArrayAssign),
+            Self::IntPtr(ptr)
+            | Self::BoolPtr(ptr)
+            | Self::SizedPtr(ptr, _)
+            | Self::PtrPtr(ptr, _) => ptr,
             _ => unreachable!(),
         }
     }
 }
+
+/// Generates a call to the given intrinsic.
+pub fn call_intrinsic(
+    program: &mut Program,
+    scopes: &Scopes,
+    intrinsic: Intrinsic,
+    args: Vec<IrValue>,
+) -> IrValue {
+    let info = current_fun!(scopes);
+    let call = info
+        .new_value(program)
+        .call(scopes.intrinsics[&intrinsic], args);
+    info.push_inst(program, call);
+    call
+}
+
+/// Generates a load of a value of the given type from the pointer,
+/// `I64` values are copied to a new temporary.
+pub fn load(program: &mut Program, scopes: &Scopes, ptr: IrValue, ty: &Ty) -> IrValue {
+    match ty {
+        Ty::Sized(IntTy::I64) => {
+            let (lo, hi) = words(program, scopes, ptr);
+            new_long(program, scopes, lo, hi)
+        }
+        Ty::Sized(ty) if ty.size() < 4 => {
+            call_intrinsic(program, scopes, Intrinsic::load(*ty), vec![ptr])
+        }
+        _ => {
+            let info = current_fun!(scopes);
+            let load = info.new_value(program).load(ptr);
+            info.push_inst(program, load);
+            load
+        }
+    }
+}
+
+/// Generates a store of a value of the given type to the pointer.
+pub fn store(program: &mut Program, scopes: &Scopes, value: IrValue, ptr: IrValue, ty: &Ty) {
+    match ty {
+        Ty::Sized(IntTy::I64) => {
+            let (lo, hi) = words(program, scopes, value);
+            let info = current_fun!(scopes);
+            let store = info.new_value(program).store(lo, ptr);
+            info.push_inst(program, store);
+            let one = info.new_value(program).integer(1);
+            let ptr = info.new_value(program).get_ptr(ptr, one);
+            info.push_inst(program, ptr);
+            let store = info.new_value(program).store(hi, ptr);
+            info.push_inst(program, store);
+        }
+        Ty::Sized(ty) if ty.size() < 4 => {
+            call_intrinsic(program, scopes, Intrinsic::store(*ty), vec![value, ptr]);
+        }
+        _ => {
+            let info = current_fun!(scopes);
+            let store = info.new_value(program).store(value, ptr);
+            info.push_inst(program, store);
+        }
+    }
+}
+
+/// Generates a temporary of two words for an `I64` value,
+/// returns the pointer to its low word, which is the value.
+///
+/// Koopa IR only has 32-bit integers, so `I64` values are passed around as
+/// pointers to their words. Every value has its own temporary, which is
+/// allocated once in the entry block and written each time the value is generated.
+pub fn alloc_long(program: &mut Program, scopes: &Scopes) -> IrValue {
+    let info = current_fun!(scopes);
+    let alloc = info.new_alloc(program, Type::get_array(Type::get_i32(), 2), None);
+    long_ptr(program, scopes, alloc)
+}
+
+/// Generates the pointer to the low word of an `I64` variable,
+/// which is an array of two words.
+pub fn long_ptr(program: &mut Program, scopes: &Scopes, var: IrValue) -> IrValue {
+    let info = current_fun!(scopes);
+    let zero = info.new_value(program).integer(0);
+    let ptr = info.new_value(program).get_elem_ptr(var, zero);
+    info.push_inst(program, ptr);
+    ptr
+}
+
+/// Generates a temporary holding an `I64` value with the given words.
+pub fn new_long(program: &mut Program, scopes: &Scopes, lo: IrValue, hi: IrValue) -> IrValue {
+    let ptr = alloc_long(program, scopes);
+    store(program, scopes, lo, ptr, &Ty::Int);
+    let info = current_fun!(scopes);
+    let one = info.new_value(program).integer(1);
+    let hi_ptr = info.new_value(program).get_ptr(ptr, one);
+    info.push_inst(program, hi_ptr);
+    store(program, scopes, hi, hi_ptr, &Ty::Int);
+    ptr
+}
+
+/// Generates the low and the high word of an `I64` value,
+/// words are integer constants converted to `I64`, which are sign-extended.
+pub fn words(program: &mut Program, scopes: &Scopes, value: IrValue) -> (IrValue, IrValue) {
+    let info = current_fun!(scopes);
+    if is_word(program, scopes, value) {
+        let shift = info.new_value(program).integer(31);
+        let hi = info.new_value(program).binary(BinaryOp::Sar, value, shift);
+        info.push_inst(program, hi);
+        return (value, hi);
+    }
+    let lo = info.new_value(program).load(value);
+    info.push_inst(program, lo);
+    let one = info.new_value(program).integer(1);
+    let ptr = info.new_value(program).get_ptr(value, one);
+    info.push_inst(program, ptr);
+    let hi = info.new_value(program).load(ptr);
+    info.push_inst(program, hi);
+    (lo, hi)
+}
+
+/// Converts an integer into an `I64` value, words are sign-extended.
+pub fn into_long(program: &mut Program, scopes: &Scopes, value: IrValue) -> IrValue {
+    if !is_word(program, scopes, value) {
+        return value;
+    }
+    let (lo, hi) = words(program, scopes, value);
+    new_long(program, scopes, lo, hi)
+}
+
+/// Generates the low word of an integer, which is the integer itself
+/// if it is not an `I64` value.
+pub fn low_word(program: &mut Program, scopes: &Scopes, value: IrValue) -> IrValue {
+    if is_word(program, scopes, value) {
+        return value;
+    }
+    let info = current_fun!(scopes);
+    let lo = info.new_value(program).load(value);
+    info.push_inst(program, lo);
+    lo
+}
+
+/// Returns `true` if the value is a word, not a pointer.
+fn is_word(program: &Program, scopes: &Scopes, value: IrValue) -> bool {
+    scopes.ty(program, value).is_i32()
+}
@@ -0,0 +1,157 @@
+//! Compiler of Ophelia, a minimalist imperative language compiled to RISC-V.
+//!
+//! The stages of the compiler are exposed as modules: [`syntax`] parses and
+//! checks the source, [`sema`] checks names and types, [`ir`] generates Koopa IR
+//! and [`codegen`] generates RISC-V assembly. [`compile`] runs the whole pipeline on a source string.
+
+pub mod ast;
+pub mod codegen;
+pub mod error;
+pub mod ir;
+pub mod sema;
+pub mod session;
+pub mod syntax;
+
+use codespan_reporting::term::termcolor::ColorChoice;
+use error::{Error, FileId};
+use koopa::back::KoopaGenerator;
+use koopa::ir::Program;
+use lalrpop_util::lalrpop_mod;
+use session::Session;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+lalrpop_mod! {
+  #[allow(clippy::all)]
+  ophelia
+}
+
+/// Kind of output to emit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Emit {
+    Ast,
+    Koopa,
+    Riscv,
+}
+
+impl FromStr for Emit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ast" => Ok(Self::Ast),
+            "koopa" => Ok(Self::Koopa),
+            "riscv" => Ok(Self::Riscv),
+            _ => Err(format!("unknown emit kind `{}`", s)),
+        }
+    }
+}
+
+/// Options of [`compile`].
+pub struct Options {
+    /// Kind of output to emit.
+    pub emit: Emit,
+    /// File name shown in diagnostics.
+    pub file_name: String,
+    /// Color choice used when emitting diagnostics.
+    pub color: ColorChoice,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            emit: Emit::Riscv,
+            file_name: String::from("<input>"),
+            color: ColorChoice::Never,
+        }
+    }
+}
+
+/// Output of a successful compilation.
+pub enum Output {
+    Ast(ast::CompUnit),
+    Koopa(Program),
+    Riscv(String),
+}
+
+impl Output {
+    /// Writes the textual form of the output.
+    pub fn write_to(&self, w: &mut dyn Write) -> io::Result<()> {
+        match self {
+            Self::Ast(ast) => writeln!(w, "{:#?}", ast),
+            Self::Koopa(program) => KoopaGenerator::new(w).generate_on(program),
+            Self::Riscv(asm) => w.write_all(asm.as_bytes()),
+        }
+    }
+}
+
+/// Diagnostics of a failed compilation.
+pub struct Diagnostics {
+    session: Session,
+    file: FileId,
+}
+
+impl Diagnostics {
+    /// Returns the reported errors.
+    pub fn errors(&self) -> &[Error] {
+        self.session.diagnostics()
+    }
+
+    /// Returns the name of the compiled file.
+    pub fn file_name(&self) -> String {
+        self.session.name(self.file)
+    }
+
+    /// Writes all diagnostics to stderr.
+    pub fn emit(&self) {
+        self.session.emit()
+    }
+}
+
+impl fmt::Debug for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(self.errors().iter().map(|e| (&e.code, &e.message)))
+            .finish()
+    }
+}
+
+/// Compiles the given source code.
+///
+/// Stops after the stage producing the output requested by `options.emit`.
+/// Returns all diagnostics if the source has errors.
+pub fn compile(source: &str, options: Options) -> Result<Output, Diagnostics> {
+    let mut session = Session::new(options.color);
+    let file = session.add_file(options.file_name, source.to_owned());
+    let failed = |session| Diagnostics { session, file };
+
+    // parse and check the source
+    let ast = syntax::parse(&mut session, file);
+    if let Some(ast) = &ast {
+        syntax::CheckerManager::new(ast, file).run(&mut session);
+    }
+    let Some(ast) = ast else {
+        return Err(failed(session));
+    };
+    if options.emit == Emit::Ast && !session.has_errors() {
+        return Ok(Output::Ast(ast));
+    }
+
+    // check names and types, still checks the well-formed parts if there are syntax errors
+    let types = sema::check(&ast, &mut session, file);
+    if session.has_errors() {
+        return Err(failed(session));
+    }
+
+    // generate IR
+    let program = ir::gen(&ast, &types);
+    if options.emit == Emit::Koopa {
+        return Ok(Output::Koopa(program));
+    }
+
+    // generate RISC-V assembly
+    let mut asm = Vec::new();
+    codegen::generate_asm(&program, &mut asm).expect("writing to a `Vec` never fails");
+    Ok(Output::Riscv(String::from_utf8(asm).unwrap()))
+}
@@ -0,0 +1,1570 @@
+use super::error::{Error, Result};
+use super::error::{
+    E0002, E0003, E0004, E0005, E0006, E0007, E0008, E0009, E0010, E0023, E0024, E0027, E0028,
+    E0029, E0030, E0031, E0042, E0048,
+};
+use super::eval::Evaluate;
+use super::scopes::{Scopes, Signature, Symbol};
+use super::ty::{Field, StructInfo, Ty};
+use crate::ast::{self, *};
+use std::collections::HashMap;
+
+/// Trait for checking names and types of the AST.
+pub trait Check<'ast> {
+    type Out;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out>;
+}
+
+/// Kind of a left value.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Place {
+    /// A value in memory, which can be assigned to and has an address.
+    Var,
+    /// A value that is not in memory, like a constant, or the pointer
+    /// to the first element of a fixed-width integer array.
+    Value,
+}
+
+impl<'ast> Check<'ast> for CompUnit {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let mut new_decl = |name, params, ret| {
+            scopes.new_fun(name, Signature { params, ret }).unwrap();
+        };
+        // declare SysY library functions, which only take integers and pointers to integers
+        let int_ptr = || Ty::Pointer(Box::new(Ty::Int));
+        new_decl("getint", vec![], Ty::Int);
+        new_decl("getch", vec![], Ty::Int);
+        new_decl("getarray", vec![int_ptr()], Ty::Int);
+        new_decl("putint", vec![Ty::Int], Ty::Unit);
+        new_decl("putch", vec![Ty::Int], Ty::Unit);
+        new_decl("putarray", vec![Ty::Int, int_ptr()], Ty::Unit);
+        new_decl("putstr", vec![int_ptr()], Ty::Unit);
+        new_decl("starttime", vec![], Ty::Unit);
+        new_decl("stoptime", vec![], Ty::Unit);
+        // check global items
+        for item in &self.items {
+            if let Err(e) = item.check(scopes) {
+                scopes.report(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'ast> Check<'ast> for GlobalItem {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        match self {
+            Self::Decl(decl) => decl.check(scopes),
+            Self::FuncDef(def) => def.check(scopes),
+            Self::Struct(def) => def.check(scopes),
+            Self::Error(_) => Ok(()),
+        }
+    }
+}
+
+impl<'ast> Check<'ast> for StructDef {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let mut fields = HashMap::new();
+        // end of the previous field in bytes
+        let mut end: usize = 0;
+        // the struct is still defined if a field is invalid,
+        // the field has the error type
+        for field in &self.fields {
+            let ty = field.check_in(scopes, &self.id.name).unwrap_or_else(|e| {
+                scopes.report(e);
+                Ty::Error
+            });
+            // lay out the field after the previous one
+            let (size, align) = scopes.types.layout(&ty);
+            let offset = end.next_multiple_of(align);
+            end = offset + size;
+            if fields
+                .insert(field.id.name.as_str(), Field { ty, offset })
+                .is_some()
+            {
+                let error = Error::DuplicatedDef.at(scopes.file(), &field.id.pos);
+                scopes.report(error);
+            }
+        }
+        let info = StructInfo {
+            size: end.div_ceil(4),
+            fields,
+        };
+        scopes
+            .new_struct(&self.id.name, info)
+            .map_err(|e| e.at(scopes.file(), &self.id.pos))
+    }
+}
+
+impl<'ast> FieldDef {
+    /// Checks the type of a field of the given struct,
+    /// the field can point to the struct itself.
+    fn check_in(&'ast self, scopes: &mut Scopes<'ast>, owner: &'ast str) -> Result<Ty<'ast>> {
+        // get element type
+        let elem = match &self.ty {
+            ast::Type::Int(_) => Ty::Int,
+            ast::Type::Unit(pos) => return Err(Error::UseVoidValue.at(scopes.file(), pos)),
+            ast::Type::Bool(pos) => return Err(Error::UnsupportedBool.at(scopes.file(), pos)),
+            ast::Type::Sized(ty, _) => Ty::Sized(*ty),
+            ast::Type::Pointer(base, _) => Ty::Pointer(Box::new(pointee_ty(base, scopes, owner)?)),
+            ast::Type::Struct(id) => {
+                scopes
+                    .struct_info(&id.name)
+                    .map_err(|e| e.at(scopes.file(), &id.pos))?;
+                Ty::Struct(&id.name)
+            }
+        };
+        let lens = to_lens(&self.dims, scopes)?;
+        Ok(Ty::array(elem, &lens))
+    }
+}
+
+impl<'ast> Check<'ast> for Decl {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        match self {
+            Self::Const(c) => c.check(scopes),
+            Self::Var(v) => v.check(scopes),
+        }
+    }
+}
+
+impl<'ast> Check<'ast> for ConstDecl {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        for def in &self.defs {
+            if let Err(e) = def.check(scopes) {
+                scopes.report(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'ast> Check<'ast> for ConstDef {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        // check the declared type, the constant is still defined on mismatch
+        if let Err(e) = (E0023::E0023 { ast: self }).run(scopes) {
+            scopes.report(e);
+        }
+        // fixed-width integer constants can not be arrays
+        let ty = match &self.ty {
+            ast::Type::Sized(_, pos) if !self.dims.is_empty() => {
+                return Err(Error::UnsupportedSized.at(scopes.file(), pos))
+            }
+            ast::Type::Sized(ty, _) => Ty::Sized(*ty),
+            _ => Ty::Int,
+        };
+        // check dimensions and initializer
+        let lens = to_lens(&self.dims, scopes)?;
+        let init = self.init.check_of(&ty, scopes)?;
+        init.fits(&lens)
+            .map_err(|e| e.at(scopes.file(), &self.id.pos))?;
+        // constant arrays are variables
+        let symbol = match (&self.init, lens.is_empty()) {
+            (ConstInitVal::Expr(exp), true) => Symbol::Const(scopes.types.value(&exp.exp), ty),
+            _ => Symbol::Var(Ty::array(Ty::Int, &lens)),
+        };
+        scopes
+            .new_value(&self.id.name, symbol)
+            .map_err(|e| e.at(scopes.file(), &self.id.pos))
+    }
+}
+
+impl<'ast> ConstInitVal {
+    /// Checks the elements of the initializer against the given element type,
+    /// returns the shape of the initializer.
+    fn check_of(&'ast self, ty: &Ty<'ast>, scopes: &mut Scopes<'ast>) -> Result<Shape> {
+        Ok(match self {
+            Self::Expr(exp) => {
+                exp.check_of(ty, scopes)?;
+                Shape::Elem
+            }
+            Self::List(list) => Shape::List(
+                list.iter()
+                    .map(|v| v.check_of(ty, scopes))
+                    .collect::<Result<_>>()?,
+            ),
+        })
+    }
+}
+
+impl<'ast> Check<'ast> for VarDecl {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        for def in &self.defs {
+            if let Err(e) = def.check(scopes) {
+                scopes.report(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'ast> Check<'ast> for VarDef {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        // check the annotated type, the variable is still defined as Int if invalid
+        if let Err(e) = (E0024::E0024 { ast: self }).run(scopes) {
+            scopes.report(e);
+        }
+        // the variable is still defined if the checks fail, it has the error type
+        // if its type is invalid, so that its uses are not reported
+        let (ty, checked) = match self.check_ty(scopes) {
+            Ok((elem, lens)) => {
+                let checked = self.check_init(scopes, &elem, &lens);
+                (Ty::array(elem, &lens), checked)
+            }
+            Err(e) => (Ty::Error, Err(e)),
+        };
+        scopes.types.insert_ty(self, ty.clone());
+        if let Err(e) = checked {
+            scopes.report(e);
+        }
+        scopes
+            .new_value(&self.id.name, Symbol::Var(ty))
+            .map_err(|e| e.at(scopes.file(), &self.id.pos))
+    }
+}
+
+impl<'ast> VarDef {
+    /// Checks the type of the variable, returns the element type
+    /// and the lengths of the dimensions.
+    fn check_ty(&'ast self, scopes: &mut Scopes<'ast>) -> Result<(Ty<'ast>, Vec<usize>)> {
+        let elem = match &self.ty {
+            // Bools can not be arrays
+            Some(ty @ ast::Type::Bool(_)) => {
+                if !self.dims.is_empty() {
+                    return Err(Error::UnsupportedBool.at(scopes.file(), ty.pos()));
+                }
+                value_ty(ty, scopes)?
+            }
+            Some(ty @ (ast::Type::Sized(..) | ast::Type::Struct(_) | ast::Type::Pointer(..))) => {
+                value_ty(ty, scopes)?
+            }
+            _ => Ty::Int,
+        };
+        Ok((elem, to_lens(&self.dims, scopes)?))
+    }
+
+    /// Checks the initializer against the element type and the dimensions,
+    /// structs are initialized like arrays of words.
+    fn check_init(
+        &'ast self,
+        scopes: &mut Scopes<'ast>,
+        elem: &Ty<'ast>,
+        lens: &[usize],
+    ) -> Result<()> {
+        match (&self.init, elem) {
+            // arrays of pointers are not initialized
+            (Some(_), Ty::Pointer(_)) if !lens.is_empty() => {
+                Err(Error::UnsupportedPointer.at(scopes.file(), &self.id.pos))
+            }
+            (Some(InitVal::List(_)), Ty::Bool) if scopes.is_global() => {
+                Err(Error::FailedToEval.at(scopes.file(), &self.id.pos))
+            }
+            (Some(init), Ty::Struct(name)) => {
+                let mut lens = lens.to_vec();
+                lens.push(scopes.struct_info(name).unwrap().size);
+                init.check_of(&Ty::Int, scopes)?
+                    .fits(&lens)
+                    .map_err(|e| e.at(scopes.file(), &self.id.pos))
+            }
+            (Some(init), elem) => init
+                .check_of(elem, scopes)?
+                .fits(lens)
+                .map_err(|e| e.at(scopes.file(), &self.id.pos)),
+            (None, _) => Ok(()),
+        }
+    }
+}
+
+impl<'ast> InitVal {
+    /// Checks the elements of the initializer against the given element type,
+    /// returns the shape of the initializer.
+    fn check_of(&'ast self, ty: &Ty<'ast>, scopes: &mut Scopes<'ast>) -> Result<Shape> {
+        Ok(match self {
+            Self::Expr(exp) => {
+                // global values are initialized by constant expressions
+                if scopes.is_global() {
+                    (E0002::E0002 { ast: exp }).run(scopes)?;
+                }
+                let found = exp.check(scopes)?;
+                expect(scopes, exp, &found, ty).map_err(|e| e.at(scopes.file(), &exp.pos))?;
+                Shape::Elem
+            }
+            Self::List(list) => Shape::List(
+                list.iter()
+                    .map(|v| v.check_of(ty, scopes))
+                    .collect::<Result<_>>()?,
+            ),
+        })
+    }
+}
+
+/// Shape of an initializer.
+pub enum Shape {
+    Elem,
+    List(Vec<Shape>),
+}
+
+impl Shape {
+    /// Checks that the initializer fits an array with the given dimension lengths,
+    /// or a single value if there are no dimensions.
+    fn fits(&self, lens: &[usize]) -> std::result::Result<(), Error> {
+        match self {
+            Self::Elem if lens.is_empty() => Ok(()),
+            Self::List(list) if !lens.is_empty() => Self::fits_list(list, lens),
+            _ => Err(Error::InvalidInit),
+        }
+    }
+
+    /// Checks the elements of a list, which fill the array in order. A nested list
+    /// fills the largest sub-array that starts at the current element, other than
+    /// the whole array, and must start at the boundary of a sub-array.
+    fn fits_list(list: &[Self], lens: &[usize]) -> std::result::Result<(), Error> {
+        // sizes of the sub-arrays, array `a[2][3][4]` yields [4, 12, 24]
+        let sizes: Vec<_> = lens
+            .iter()
+            .rev()
+            .scan(1, |size, len| {
+                *size *= len;
+                Some(*size)
+            })
+            .collect();
+        let (total, sizes) = sizes.split_last().unwrap();
+        let mut len = 0;
+        for init in list {
+            // too many elements
+            if len >= *total {
+                return Err(Error::InvalidInit);
+            }
+            match init {
+                Self::List(list) => {
+                    let dims = sizes.iter().take_while(|&&size| len % size == 0).count();
+                    // not aligned
+                    if dims == 0 {
+                        return Err(Error::InvalidInit);
+                    }
+                    Self::fits_list(list, &lens[lens.len() - dims..])?;
+                    len += sizes[dims - 1];
+                }
+                Self::Elem => len += 1,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks the lengths of array dimensions, returns the lengths.
+fn to_lens<'ast>(dims: &'ast [ConstExpr], scopes: &mut Scopes<'ast>) -> Result<Vec<usize>> {
+    dims.iter()
+        .map(|exp| {
+            let len = exp.check(scopes)?;
+            if len >= 1 {
+                Ok(len as usize)
+            } else {
+                Err(Error::InvalidArrayLen.at(scopes.file(), &exp.pos))
+            }
+        })
+        .collect()
+}
+
+/// Returns the type of values of the given type, which must not be `Unit`.
+fn value_ty<'ast>(ty: &'ast ast::Type, scopes: &Scopes<'ast>) -> Result<Ty<'ast>> {
+    match ty {
+        ast::Type::Int(_) => Ok(Ty::Int),
+        ast::Type::Bool(_) => Ok(Ty::Bool),
+        ast::Type::Sized(ty, _) => Ok(Ty::Sized(*ty)),
+        ast::Type::Unit(pos) => Err(Error::UseVoidValue.at(scopes.file(), pos)),
+        ast::Type::Struct(id) => {
+            scopes
+                .struct_info(&id.name)
+                .map_err(|e| e.at(scopes.file(), &id.pos))?;
+            Ok(Ty::Struct(&id.name))
+        }
+        ast::Type::Pointer(base, _) => Ok(Ty::Pointer(Box::new(value_ty(base, scopes)?))),
+    }
+}
+
+/// Returns the type of values a pointer field of the given struct points to,
+/// which can be the struct itself.
+fn pointee_ty<'ast>(
+    ty: &'ast ast::Type,
+    scopes: &Scopes<'ast>,
+    owner: &'ast str,
+) -> Result<Ty<'ast>> {
+    match ty {
+        ast::Type::Struct(id) if id.name == owner => Ok(Ty::Struct(owner)),
+        ast::Type::Pointer(base, _) => Ok(Ty::Pointer(Box::new(pointee_ty(base, scopes, owner)?))),
+        ty => value_ty(ty, scopes),
+    }
+}
+
+impl<'ast> Check<'ast> for FuncDef {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        // check parameter types and return type, the function is still defined
+        // if they are invalid, they have the error type
+        let mut params = Vec::new();
+        let mut vars = Vec::new();
+        for param in &self.params {
+            let (ty, var) = param.check(scopes).unwrap_or_else(|e| {
+                scopes.report(e);
+                (Ty::Error, Ty::Error)
+            });
+            scopes.types.insert_ty(param, var.clone());
+            params.push(ty);
+            vars.push(var);
+        }
+        let ret = self.ty.check(scopes).unwrap_or_else(|e| {
+            scopes.report(e);
+            Ty::Error
+        });
+        scopes.types.insert_ty(&self.ty, ret.clone());
+        // define parameters, the function can be called in its body
+        scopes.ret_ty = Some(ret.clone());
+        scopes.enter();
+        for (param, ty) in self.params.iter().zip(vars) {
+            if let Err(e) = scopes.new_value(&param.id.name, Symbol::Var(ty)) {
+                scopes.report(e.at(scopes.file(), &param.id.pos));
+            }
+        }
+        if let Err(e) = scopes.new_fun(&self.id.name, Signature { params, ret }) {
+            scopes.report(e.at(scopes.file(), &self.id.pos));
+        }
+        // check funtion body
+        let body = self.block.check(scopes);
+        scopes.exit();
+        scopes.ret_ty = None;
+        body?;
+        (E0028::E0028 { ast: self }).run(scopes)
+    }
+}
+
+impl<'ast> Check<'ast> for ast::Type {
+    type Out = Ty<'ast>;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        match self {
+            Self::Unit(_) => Ok(Ty::Unit),
+            Self::Struct(id) => Err(Error::UnsupportedStruct.at(scopes.file(), &id.pos)),
+            ty => value_ty(ty, scopes),
+        }
+    }
+}
+
+impl<'ast> Check<'ast> for FuncFParam {
+    /// Type of the parameter in the signature, and type of the variable.
+    type Out = (Ty<'ast>, Ty<'ast>);
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let ty = match (&self.typ, &self.dims) {
+            (ast::Type::Struct(_) | ast::Type::Pointer(..) | ast::Type::Bool(_), None) => {
+                value_ty(&self.typ, scopes)?
+            }
+            (ast::Type::Sized(..), None) => value_ty(&self.typ, scopes)?,
+            // fixed-width integer and pointer arrays with one dimension are pointers
+            (ast::Type::Sized(..) | ast::Type::Pointer(..), Some(dims)) if dims.is_empty() => {
+                Ty::Pointer(Box::new(value_ty(&self.typ, scopes)?))
+            }
+            (ast::Type::Sized(..), Some(_)) => {
+                return Err(Error::UnsupportedSized.at(scopes.file(), &self.pos))
+            }
+            (ast::Type::Bool(_), Some(_)) => {
+                return Err(Error::UnsupportedBool.at(scopes.file(), &self.pos))
+            }
+            (ast::Type::Struct(_), Some(_)) => {
+                return Err(Error::UnsupportedStruct.at(scopes.file(), &self.pos))
+            }
+            (ast::Type::Pointer(..), Some(_)) => {
+                return Err(Error::UnsupportedPointer.at(scopes.file(), &self.pos))
+            }
+            // integer arrays are passed as pointers to their first element,
+            // but can not be used as pointers in the function
+            (_, Some(dims)) if dims.is_empty() => {
+                let var = Ty::Array(Box::new(Ty::Int), None);
+                return Ok((Ty::Pointer(Box::new(Ty::Int)), var));
+            }
+            (_, Some(dims)) => {
+                let lens = to_lens(dims, scopes)?;
+                Ty::Array(Box::new(Ty::array(Ty::Int, &lens)), None)
+            }
+            (_, None) => Ty::Int,
+        };
+        Ok((ty.clone(), ty))
+    }
+}
+
+impl<'ast> Check<'ast> for Block {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        scopes.enter();
+        for item in &self.items {
+            if let Err(e) = item.check(scopes) {
+                scopes.report(e);
+            }
+        }
+        scopes.exit();
+        Ok(())
+    }
+}
+
+impl<'ast> Check<'ast> for BlockItem {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        match self {
+            Self::Decl(decl) => decl.check(scopes),
+            Self::Stmt(stmt) => stmt.check(scopes),
+        }
+    }
+}
+
+impl<'ast> Check<'ast> for Stmt {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        match self {
+            Self::Assign(s) => s.check(scopes),
+            Self::CompoundAssign(s) => s.check(scopes),
+            Self::Increment(s) => s.check(scopes),
+            Self::ExprStmt(s) => s.check(scopes),
+            Self::Block(s) => s.check(scopes),
+            Self::If(s) => s.check(scopes),
+            Self::While(s) => s.check(scopes),
+            Self::Loop(s) => s.check(scopes),
+            Self::DoWhile(s) => s.check(scopes),
+            Self::Match(s) => s.check(scopes),
+            Self::For(s) => s.check(scopes),
+            Self::ForRange(s) => s.check(scopes),
+            Self::Break(s) => s.check(scopes),
+            Self::Continue(s) => s.check(scopes),
+            Self::Return(s) => s.check(scopes),
+            Self::Error(_) => Ok(()),
+        }
+    }
+}
+
+impl<'ast> Check<'ast> for Assign {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let exp = self.exp.check(scopes)?;
+        let (ty, place) = self.lval.check(scopes)?;
+        assignable(&ty, place).map_err(|e| e.at(scopes.file(), &self.lval.pos))?;
+        expect(scopes, &self.exp, &exp, &ty).map_err(|e| e.at(scopes.file(), &self.exp.pos))
+    }
+}
+
+impl<'ast> Check<'ast> for CompoundAssign {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let (ty, place) = self.lval.check(scopes)?;
+        assignable(&ty, place).map_err(|e| e.at(scopes.file(), &self.lval.pos))?;
+        if ty == Ty::Bool {
+            return Err(Error::BoolCalc.at(scopes.file(), &self.lval.pos));
+        }
+        let exp = self.exp.check(scopes)?;
+        let is_shift = matches!(
+            self.op,
+            CompoundOp::Shl(_) | CompoundOp::Sar(_) | CompoundOp::Shr(_)
+        );
+        // shift amounts and pointer offsets can be of any integer type
+        let checked = match &ty {
+            Ty::Sized(_) if !is_shift => expect(scopes, &self.exp, &exp, &ty),
+            Ty::Sized(_) | Ty::Pointer(_) => {
+                not_large_literal(scopes, &self.exp).and_then(|_| expect_any_int(&exp))
+            }
+            _ => expect(scopes, &self.exp, &exp, &Ty::Int),
+        };
+        checked.map_err(|e| e.at(scopes.file(), &self.exp.pos))?;
+        match (ty, &self.op) {
+            (Ty::Pointer(_), CompoundOp::Add(_) | CompoundOp::Sub(_)) => Ok(()),
+            (Ty::Pointer(_), _) => Err(Error::PointerMismatch.at(scopes.file(), &self.lval.pos)),
+            (Ty::Sized(IntTy::I64), CompoundOp::Div(_) | CompoundOp::Mod(_)) => {
+                Err(Error::I64Division.at(scopes.file(), &self.pos))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<'ast> Check<'ast> for Increment {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let (ty, place) = self.lval.check(scopes)?;
+        assignable(&ty, place).map_err(|e| e.at(scopes.file(), &self.lval.pos))?;
+        match ty {
+            Ty::Bool => Err(Error::BoolCalc.at(scopes.file(), &self.lval.pos)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Checks that a left value of the given type can be assigned to.
+fn assignable(ty: &Ty, place: Place) -> std::result::Result<(), Error> {
+    match (ty, place) {
+        (Ty::Array(..), _) => Err(Error::ArrayAssign),
+        (Ty::Struct(_), _) => Err(Error::StructValue),
+        (_, Place::Value) => Err(Error::ConstAssign),
+        _ => Ok(()),
+    }
+}
+
+impl<'ast> Check<'ast> for ExprStmt {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        if let Some(exp) = &self.exp {
+            exp.check(scopes)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'ast> Check<'ast> for If {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        if let Err(e) = (E0042::E0042 { ast: &self.cond }).run(scopes) {
+            scopes.report(e);
+        }
+        self.then.check(scopes)?;
+        if let Some(else_then) = &self.else_then {
+            else_then.check(scopes)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'ast> Check<'ast> for While {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        if let Err(e) = (E0042::E0042 { ast: &self.cond }).run(scopes) {
+            scopes.report(e);
+        }
+        check_loop(scopes, &self.label, &self.body)
+    }
+}
+
+impl<'ast> Check<'ast> for Loop {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        check_loop(scopes, &self.label, &self.body)
+    }
+}
+
+impl<'ast> Check<'ast> for DoWhile {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        check_loop(scopes, &self.label, &self.body)?;
+        (E0042::E0042 { ast: &self.cond }).run(scopes)
+    }
+}
+
+impl<'ast> Check<'ast> for For {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        // the induction variables live in their own scope
+        scopes.enter();
+        if let Some(init) = &self.init {
+            if let Err(e) = init.check(scopes) {
+                scopes.report(e);
+            }
+        }
+        if let Some(cond) = &self.cond {
+            if let Err(e) = (E0042::E0042 { ast: cond }).run(scopes) {
+                scopes.report(e);
+            }
+        }
+        let result = check_loop(scopes, &self.label, &self.body)
+            .and_then(|_| self.step.as_ref().map_or(Ok(()), |s| s.check(scopes)));
+        scopes.exit();
+        result
+    }
+}
+
+impl<'ast> Check<'ast> for ForRange {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        // check the bounds before defining the induction variable
+        for bound in [&self.start, &self.end] {
+            let ty = bound.check(scopes)?;
+            expect(scopes, bound, &ty, &Ty::Int).map_err(|e| e.at(scopes.file(), &bound.pos))?;
+        }
+        scopes.enter();
+        let result = scopes
+            .new_value(&self.id.name, Symbol::Var(Ty::Int))
+            .map_err(|e| e.at(scopes.file(), &self.id.pos))
+            .and_then(|_| check_loop(scopes, &self.label, &self.body));
+        scopes.exit();
+        result
+    }
+}
+
+/// Checks the body of a loop, reports if the label shadows an outer one.
+fn check_loop<'ast>(
+    scopes: &mut Scopes<'ast>,
+    label: &'ast Option<Label>,
+    body: &'ast impl Check<'ast, Out = ()>,
+) -> Result<()> {
+    if let Some(label) = label {
+        if let Err(e) = (E0027::E0027 { ast: label }).run(scopes) {
+            scopes.report(e);
+        }
+    }
+    scopes.loops.push(label.as_ref());
+    let result = body.check(scopes);
+    scopes.loops.pop();
+    result
+}
+
+impl<'ast> Check<'ast> for Match {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        if let Err(e) = (E0030::E0030 { ast: self }).run(scopes) {
+            scopes.report(e);
+        }
+        let ty = self.exp.check(scopes)?;
+        expect(scopes, &self.exp, &ty, &Ty::Int).map_err(|e| e.at(scopes.file(), &self.exp.pos))?;
+        // patterns after a wildcard are never tested
+        'arms: for arm in &self.arms {
+            for pat in &arm.pats {
+                if let Pattern::Wildcard(_) = pat {
+                    break 'arms;
+                }
+                if let Err(e) = pat.check(scopes) {
+                    scopes.report(e);
+                }
+            }
+        }
+        for arm in &self.arms {
+            if let Err(e) = arm.body.check(scopes) {
+                scopes.report(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'ast> Check<'ast> for Pattern {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        match self {
+            Self::Value(v) => v.check(scopes).map(|_| ()),
+            Self::Range(lo, hi) => {
+                let (lo_val, hi_val) = (lo.check(scopes)?, hi.check(scopes)?);
+                (E0031::E0031 { lo, hi }).run(scopes, lo_val, hi_val)
+            }
+            Self::Wildcard(_) => Ok(()),
+        }
+    }
+}
+
+impl<'ast> Check<'ast> for PatValue {
+    type Out = i32;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        match self {
+            Self::Number(num) => Ok(num.value),
+            Self::Const(id) => match scopes.value(&id.name) {
+                // the matched values are Ints
+                Ok(Symbol::Const(v, Ty::Int)) => Ok(*v),
+                Ok(Symbol::Const(..)) => Err(Error::IntMismatch.at(scopes.file(), &id.pos)),
+                Ok(_) => Err(Error::FailedToEval.at(scopes.file(), &id.pos)),
+                Err(e) => Err(e.at(scopes.file(), &id.pos)),
+            },
+        }
+    }
+}
+
+impl<'ast> Check<'ast> for Break {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        (E0003::E0003 { ast: self }).run(scopes)
+    }
+}
+
+impl<'ast> Check<'ast> for Continue {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        (E0005::E0005 { ast: self }).run(scopes)
+    }
+}
+
+impl<'ast> Check<'ast> for Return {
+    type Out = ();
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let ty = scopes.ret_ty.clone().unwrap();
+        match (&ty, &self.exp) {
+            (Ty::Unit, Some(_)) => (E0004::E0004 { ast: self }).run(scopes),
+            (Ty::Unit | Ty::Error, None) => Ok(()),
+            (ty, Some(exp)) => {
+                let found = exp.check(scopes)?;
+                expect(scopes, exp, &found, ty).map_err(|e| e.at(scopes.file(), &exp.pos))
+            }
+            (ty, None) => (E0048::E0048 { ast: self }).run(scopes, ty),
+        }
+    }
+}
+
+impl<'ast> Check<'ast> for Expr {
+    type Out = Ty<'ast>;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let ty = self.lor.check(scopes)?;
+        scopes.types.insert_ty(self, ty.clone());
+        Ok(ty)
+    }
+}
+
+impl<'ast> Check<'ast> for LVal {
+    type Out = (Ty<'ast>, Place);
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let (ty, place) = self.check_place(scopes)?;
+        scopes.types.insert_ty(self, ty.clone());
+        Ok((ty, place))
+    }
+}
+
+impl<'ast> LVal {
+    /// Checks the variable, indices, fields and dereferences,
+    /// returns the type and the kind of the left value.
+    fn check_place(&'ast self, scopes: &mut Scopes<'ast>) -> Result<(Ty<'ast>, Place)> {
+        let id = match &self.base {
+            LValBase::Var(id) => id,
+            LValBase::Expr(exp) => return self.check_expr(scopes, exp),
+        };
+        let ty = match scopes.value(&id.name) {
+            Ok(Symbol::Var(ty)) => ty.clone(),
+            Ok(Symbol::Const(_, ty)) => {
+                let ty = ty.clone();
+                (E0006::E0006 { ast: self }).run(scopes)?;
+                return Ok((ty, Place::Value));
+            }
+            Err(e) => {
+                scopes.report(e.at(scopes.file(), &id.pos));
+                return Ok((Ty::Error, Place::Var));
+            }
+        };
+        let (ty, place) = match ty.elem() {
+            (Ty::Pointer(_), _) => self.check_pointer(scopes, ty)?,
+            (Ty::Sized(_), _) => self.check_sized(scopes, ty)?,
+            _ => self.check_var(scopes, ty)?,
+        };
+        self.check_derefs(scopes, ty, place)
+    }
+
+    /// Checks the expression the left value starts from, which is
+    /// a pointer, an integer array with one dimension or a struct.
+    fn check_expr(
+        &'ast self,
+        scopes: &mut Scopes<'ast>,
+        exp: &'ast UnaryExpr,
+    ) -> Result<(Ty<'ast>, Place)> {
+        let (ty, place) = match exp.check(scopes)? {
+            ty @ Ty::Pointer(_) => self.check_pointer(scopes, ty)?,
+            Ty::Array(elem, _) if *elem == Ty::Int => {
+                self.check_pointer(scopes, Ty::Pointer(elem))?
+            }
+            ty @ (Ty::Error | Ty::Struct(_)) => self.check_var(scopes, ty)?,
+            _ => return Err(Error::NonPointerDeref.at(scopes.file(), &exp.span())),
+        };
+        self.check_derefs(scopes, ty, place)
+    }
+
+    /// Checks the dereferences applied after the indices and fields.
+    fn check_derefs(
+        &'ast self,
+        scopes: &Scopes<'ast>,
+        mut ty: Ty<'ast>,
+        mut place: Place,
+    ) -> Result<(Ty<'ast>, Place)> {
+        // handle pointer dereference, integer arrays with one dimension
+        // are pointers to their first element
+        for _ in 0..self.derefs {
+            ty = match ty {
+                Ty::Error => Ty::Error,
+                Ty::Pointer(ty) => *ty,
+                Ty::Array(elem, _) if *elem == Ty::Int => Ty::Int,
+                _ => return Err(Error::NonPointerDeref.at(scopes.file(), &self.pos)),
+            };
+            place = Place::Var;
+        }
+        Ok((ty, place))
+    }
+
+    /// Checks indices and fields of a variable which is an integer, a Bool,
+    /// a struct or an array of integers or structs.
+    fn check_var(
+        &'ast self,
+        scopes: &mut Scopes<'ast>,
+        mut ty: Ty<'ast>,
+    ) -> Result<(Ty<'ast>, Place)> {
+        for index in &self.indices {
+            ty = self.index(scopes, ty)?;
+            check_index(scopes, index)?;
+        }
+        match ty.elem() {
+            (Ty::Error, _) => Ok((Ty::Error, Place::Var)),
+            (Ty::Struct(_), dims) if dims != 0 => {
+                Err(Error::UnsupportedStruct.at(scopes.file(), &self.pos))
+            }
+            (Ty::Struct(name), _) => {
+                let name = *name;
+                self.check_fields(scopes, name)
+            }
+            _ => match self.fields.first() {
+                Some(field) => Err(Error::FieldNotFound.at(scopes.file(), &field.id.pos)),
+                None => Ok((ty, Place::Var)),
+            },
+        }
+    }
+
+    /// Checks indices of a fixed-width integer variable or array, which has
+    /// no fields. Arrays with one dimension left are pointers to their first element.
+    fn check_sized(
+        &'ast self,
+        scopes: &mut Scopes<'ast>,
+        mut ty: Ty<'ast>,
+    ) -> Result<(Ty<'ast>, Place)> {
+        if let Some(field) = self.fields.first() {
+            return Err(Error::FieldNotFound.at(scopes.file(), &field.id.pos));
+        }
+        for index in &self.indices {
+            ty = self.index(scopes, ty)?;
+            check_index(scopes, index)?;
+        }
+        match ty {
+            Ty::Array(elem, _) if matches!(*elem, Ty::Sized(_)) => {
+                Ok((Ty::Pointer(elem), Place::Value))
+            }
+            Ty::Array(..) => Err(Error::NonIntCalc.at(scopes.file(), &self.pos)),
+            ty => Ok((ty, Place::Var)),
+        }
+    }
+
+    /// Checks indices and fields of a pointer variable or array, indices of arrays
+    /// are checked first, and the remaining indices are offsets from the pointer.
+    /// Fields of structs are accessed through the pointer.
+    fn check_pointer(
+        &'ast self,
+        scopes: &mut Scopes<'ast>,
+        mut ty: Ty<'ast>,
+    ) -> Result<(Ty<'ast>, Place)> {
+        for index in &self.indices {
+            ty = match ty {
+                Ty::Array(ty, _) | Ty::Pointer(ty) => *ty,
+                _ => return Err(Error::NonPointerDeref.at(scopes.file(), &self.pos)),
+            };
+            check_index(scopes, index)?;
+        }
+        let Some(field) = self.fields.first() else {
+            return check_pointer_array(scopes, ty, &self.pos);
+        };
+        if let Ty::Pointer(base) = &ty {
+            if let Ty::Struct(_) = **base {
+                ty = *base.clone();
+            }
+        }
+        match ty {
+            Ty::Struct(name) => self.check_fields(scopes, name),
+            _ => Err(Error::FieldNotFound.at(scopes.file(), &field.id.pos)),
+        }
+    }
+
+    /// Checks field accesses of a struct of the given type.
+    fn check_fields(
+        &'ast self,
+        scopes: &mut Scopes<'ast>,
+        name: &'ast str,
+    ) -> Result<(Ty<'ast>, Place)> {
+        let mut ty = Ty::Struct(name);
+        for access in &self.fields {
+            // arrays have no fields
+            // fields of structs are accessed through pointer fields
+            if let Ty::Pointer(base) = &ty {
+                if let Ty::Struct(_) = **base {
+                    ty = *base.clone();
+                }
+            }
+            ty = match &ty {
+                Ty::Error => Some(Ty::Error),
+                Ty::Struct(name) => scopes
+                    .struct_info(name)
+                    .ok()
+                    .and_then(|s| s.fields.get(access.id.name.as_str()))
+                    .map(|field| field.ty.clone()),
+                _ => None,
+            }
+            .ok_or_else(|| Error::FieldNotFound.at(scopes.file(), &access.id.pos))?;
+            for index in &access.indices {
+                ty = self.index(scopes, ty)?;
+                check_index(scopes, index)?;
+            }
+        }
+        // only arrays with one dimension left can be used as pointers
+        match ty {
+            Ty::Error | Ty::Struct(_) | Ty::Int | Ty::Sized(_) => Ok((ty, Place::Var)),
+            Ty::Array(ref elem, _) if **elem == Ty::Int => Ok((ty, Place::Var)),
+            Ty::Array(elem, _) if matches!(*elem, Ty::Sized(_)) => {
+                Ok((Ty::Pointer(elem), Place::Value))
+            }
+            Ty::Pointer(_) => Ok((ty, Place::Var)),
+            Ty::Array(elem, _) if matches!(*elem, Ty::Pointer(_)) => {
+                Ok((Ty::Pointer(elem), Place::Value))
+            }
+            _ => Err(Error::UnsupportedStruct.at(scopes.file(), &self.pos)),
+        }
+    }
+
+    /// Returns the element type of the indexed array.
+    fn index(&'ast self, scopes: &Scopes<'ast>, ty: Ty<'ast>) -> Result<Ty<'ast>> {
+        match ty {
+            Ty::Array(elem, _) => Ok(*elem),
+            Ty::Error => Ok(Ty::Error),
+            _ => {
+                (E0007::E0007 { ast: self }).run(scopes, 0)?;
+                unreachable!()
+            }
+        }
+    }
+}
+
+/// Checks the type of an indexed pointer or array of pointers, arrays of
+/// pointers with one dimension left are pointers to their first element.
+fn check_pointer_array<'ast>(
+    scopes: &Scopes<'ast>,
+    ty: Ty<'ast>,
+    pos: &Position,
+) -> Result<(Ty<'ast>, Place)> {
+    match ty {
+        Ty::Array(elem, _) if matches!(*elem, Ty::Pointer(_)) => {
+            Ok((Ty::Pointer(elem), Place::Value))
+        }
+        Ty::Array(..) => Err(Error::NonIntCalc.at(scopes.file(), pos)),
+        ty => Ok((ty, Place::Var)),
+    }
+}
+
+/// Checks an array index, which can be of any integer type.
+fn check_index<'ast>(scopes: &mut Scopes<'ast>, index: &'ast Expr) -> Result<()> {
+    let ty = index.check(scopes)?;
+    not_large_literal(scopes, index)
+        .and_then(|_| expect_any_int(&ty))
+        .map_err(|e| e.at(scopes.file(), &index.pos))
+}
+
+impl<'ast> Check<'ast> for PrimaryExpr {
+    type Out = Ty<'ast>;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        match self {
+            Self::Expr(exp) => exp.check(scopes),
+            Self::LVal(lval) => lval.check(scopes).map(|(ty, _)| ty),
+            // literals above `i32::MAX` are only `U32` values
+            Self::Number(num) if num.value < 0 => {
+                scopes.new_large_literal(num);
+                Ok(Ty::Sized(IntTy::U32))
+            }
+            Self::Number(_) => Ok(Ty::Int),
+            Self::Bool(_) => Ok(Ty::Bool),
+            // string literals are zero-terminated integer arrays
+            Self::Str(s) => Ok(Ty::array(Ty::Int, &[s.value.len() + 1])),
+            Self::If(exp) => exp.check(scopes),
+        }
+    }
+}
+
+impl<'ast> Check<'ast> for IfExpr {
+    type Out = Ty<'ast>;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        (E0042::E0042 { ast: &self.cond }).run(scopes)?;
+        // the result is a Bool or a fixed-width integer if the then arm is,
+        // other values must be integers
+        let then = self.then.check(scopes)?;
+        let ty = match &then {
+            Ty::Unit | Ty::Bool | Ty::Sized(_) | Ty::Error => then.clone(),
+            _ => {
+                expect(scopes, &self.then.value, &then, &Ty::Int)
+                    .map_err(|e| e.at(scopes.file(), &self.then.value.pos))?;
+                Ty::Int
+            }
+        };
+        // the else arm must have the same type
+        let else_then = match &self.else_then {
+            ElseExpr::Block(block) => {
+                let found = block.check(scopes)?;
+                (E0029::E0029 { ast: self }).run(scopes, &then, &found)?;
+                expect_arm(scopes, block, &found, &ty)
+            }
+            ElseExpr::If(exp) => {
+                let found = exp.check(scopes)?;
+                (E0029::E0029 { ast: self }).run(scopes, &then, &found)?;
+                expect_arm(scopes, exp.as_ref(), &found, &ty)
+            }
+        };
+        else_then.map_err(|e| e.at(scopes.file(), self.else_then.value_pos()))?;
+        Ok(ty)
+    }
+}
+
+/// Checks the else arm of an `if` expression, which is `Unit` if the then arm is.
+fn expect_arm(
+    scopes: &Scopes,
+    exp: &(impl Evaluate + Span),
+    found: &Ty,
+    ty: &Ty,
+) -> std::result::Result<(), Error> {
+    match ty {
+        Ty::Unit => Ok(()),
+        ty => expect(scopes, exp, found, ty),
+    }
+}
+
+impl<'ast> Check<'ast> for ValueBlock {
+    type Out = Ty<'ast>;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        scopes.enter();
+        for item in &self.items {
+            if let Err(e) = item.check(scopes) {
+                scopes.report(e);
+            }
+        }
+        let value = self.value.check(scopes);
+        scopes.exit();
+        value
+    }
+}
+
+impl<'ast> Check<'ast> for UnaryExpr {
+    type Out = Ty<'ast>;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let ty = match self {
+            Self::Primary(exp) => exp.check(scopes),
+            Self::Call(call) => call.check(scopes),
+            Self::Unary(UnaryOp::LNot(_), exp) => {
+                let ty = exp.check(scopes)?;
+                expect(scopes, &**exp, &ty, &Ty::Bool)
+                    .map_err(|e| e.at(scopes.file(), &exp.span()))?;
+                Ok(Ty::Bool)
+            }
+            Self::Unary(_, exp) => {
+                let ty = exp.check(scopes)?;
+                not_large_literal(scopes, &**exp)
+                    .and_then(|_| expect_any_int(&ty))
+                    .map_err(|e| e.at(scopes.file(), &exp.span()))?;
+                Ok(ty)
+            }
+            Self::Cast(exp, ty) => {
+                // conversions between integers and Bools
+                let found = exp.check(scopes)?;
+                if !matches!(ty, ast::Type::Sized(IntTy::U32, _)) {
+                    not_large_literal(scopes, &**exp)
+                        .map_err(|e| e.at(scopes.file(), &exp.span()))?;
+                }
+                if found != Ty::Bool {
+                    expect_any_int(&found).map_err(|e| e.at(scopes.file(), &exp.span()))?;
+                }
+                match ty {
+                    ast::Type::Int(_) => Ok(Ty::Int),
+                    ast::Type::Bool(_) => Ok(Ty::Bool),
+                    ast::Type::Sized(ty, _) => Ok(Ty::Sized(*ty)),
+                    _ => Err(Error::InvalidCast.at(scopes.file(), ty.pos())),
+                }
+            }
+            Self::AddrOf(lval) => match lval.check(scopes)? {
+                (Ty::Error, _) => Ok(Ty::Error),
+                (Ty::Array(..), _) | (_, Place::Value) => {
+                    Err(Error::AddrOfValue.at(scopes.file(), &lval.pos))
+                }
+                (ty, Place::Var) => Ok(Ty::Pointer(Box::new(ty))),
+            },
+        }?;
+        scopes.types.insert_ty(self, ty.clone());
+        Ok(ty)
+    }
+}
+
+impl<'ast> Check<'ast> for FuncCall {
+    type Out = Ty<'ast>;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        // get funtion from scope, still checks the arguments if not found
+        let (params, ret) = match scopes.fun(&self.id.name) {
+            Ok(sig) => (sig.params.clone(), sig.ret.clone()),
+            Err(e) => {
+                scopes.report(e.at(scopes.file(), &self.id.pos));
+                for arg in &self.args {
+                    arg.check(scopes)?;
+                }
+                return Ok(Ty::Error);
+            }
+        };
+        // check arguments, integer constants take the types of the parameters
+        let mut args = Vec::new();
+        for (i, exp) in self.args.iter().enumerate() {
+            let mut ty = exp.check(scopes)?;
+            if params.get(i) != Some(&Ty::Sized(IntTy::U32)) {
+                not_large_literal(scopes, exp).map_err(|e| e.at(scopes.file(), &exp.pos))?;
+            }
+            match (&ty, params.get(i)) {
+                (Ty::Unit, _) => return Err(Error::UseVoidValue.at(scopes.file(), &exp.pos)),
+                (Ty::Int, Some(param @ Ty::Sized(_)))
+                    if expect(scopes, exp, &ty, param).is_ok() =>
+                {
+                    ty = param.clone();
+                }
+                _ => {}
+            }
+            args.push(ty);
+        }
+        let checked = (E0008::E0008 { ast: self })
+            .run(scopes, &params, &args)
+            .and_then(|_| (E0009::E0009 { ast: self }).run(scopes, &params, &args));
+        if let Err(e) = checked {
+            scopes.report(e);
+        }
+        // the call is still a value of the return type if the arguments are wrong
+        scopes.types.insert_ty(self, ret.clone());
+        Ok(ret)
+    }
+}
+
+impl<'ast> Check<'ast> for MulExpr {
+    type Out = Ty<'ast>;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let ty = match self {
+            Self::Unary(exp) => exp.check(scopes),
+            Self::MulUnary(lhs_exp, op, rhs_exp) => {
+                let lhs = lhs_exp.check(scopes)?;
+                let rhs = rhs_exp.check(scopes)?;
+                let ty = unify(scopes, (&**lhs_exp, lhs), (rhs_exp, rhs))?;
+                match (&ty, op) {
+                    (Ty::Sized(IntTy::I64), MulOp::Div(_) | MulOp::Mod(_)) => {
+                        Err(Error::I64Division.at(scopes.file(), &self.span()))
+                    }
+                    _ => Ok(ty),
+                }
+            }
+        }?;
+        scopes.types.insert_ty(self, ty.clone());
+        Ok(ty)
+    }
+}
+
+impl<'ast> Check<'ast> for AddExpr {
+    type Out = Ty<'ast>;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let ty = match self {
+            Self::Mul(exp) => exp.check(scopes),
+            Self::AddMul(lhs_exp, op, rhs_exp) => {
+                let lhs = lhs_exp.check(scopes)?;
+                let rhs = rhs_exp.check(scopes)?;
+                // handle pointer arithmetic, offsets can be of any integer type
+                match (lhs, op, rhs) {
+                    (ptr @ Ty::Pointer(_), AddOp::Add(_) | AddOp::Sub(_), n)
+                    | (n, AddOp::Add(_), ptr @ Ty::Pointer(_))
+                        if n.is_int() =>
+                    {
+                        Ok(ptr)
+                    }
+                    (lhs, _, rhs) => unify(scopes, (&**lhs_exp, lhs), (rhs_exp, rhs)),
+                }
+            }
+        }?;
+        scopes.types.insert_ty(self, ty.clone());
+        Ok(ty)
+    }
+}
+
+impl<'ast> Check<'ast> for ShiftExpr {
+    type Out = Ty<'ast>;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let ty = match self {
+            Self::Add(exp) => exp.check(scopes),
+            Self::ShiftAdd(lhs_exp, _, rhs_exp) => {
+                // the result has the type of the left-hand side,
+                // shift amounts can be of any integer type
+                let lhs = lhs_exp.check(scopes)?;
+                not_large_literal(scopes, &**lhs_exp)
+                    .and_then(|_| expect_any_int(&lhs))
+                    .map_err(|e| e.at(scopes.file(), &lhs_exp.span()))?;
+                let rhs = rhs_exp.check(scopes)?;
+                not_large_literal(scopes, rhs_exp)
+                    .and_then(|_| expect_any_int(&rhs))
+                    .map_err(|e| e.at(scopes.file(), &rhs_exp.span()))?;
+                Ok(lhs)
+            }
+        }?;
+        scopes.types.insert_ty(self, ty.clone());
+        Ok(ty)
+    }
+}
+
+impl<'ast> Check<'ast> for RelExpr {
+    type Out = Ty<'ast>;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let ty = match self {
+            Self::Shift(exp) => exp.check(scopes),
+            Self::RelShift(lhs_exp, _, rhs_exp) => {
+                let lhs = lhs_exp.check(scopes)?;
+                let rhs = rhs_exp.check(scopes)?;
+                let ty = unify(scopes, (&**lhs_exp, lhs), (rhs_exp, rhs))?;
+                scopes.types.insert_operand_ty(self, ty);
+                Ok(Ty::Bool)
+            }
+        }?;
+        scopes.types.insert_ty(self, ty.clone());
+        Ok(ty)
+    }
+}
+
+impl<'ast> Check<'ast> for EqExpr {
+    type Out = Ty<'ast>;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let ty = match self {
+            Self::Rel(exp) => exp.check(scopes),
+            Self::EqRel(lhs_exp, _, rhs_exp) => {
+                // compares two integers of the same type, two Bools
+                // or two pointers of the same type
+                let lhs = lhs_exp.check(scopes)?;
+                let rhs = rhs_exp.check(scopes)?;
+                let ty = match lhs {
+                    lhs @ (Ty::Bool | Ty::Pointer(_)) => {
+                        expect(scopes, rhs_exp, &rhs, &lhs)
+                            .map_err(|e| e.at(scopes.file(), &rhs_exp.span()))?;
+                        lhs
+                    }
+                    lhs => unify(scopes, (&**lhs_exp, lhs), (rhs_exp, rhs))?,
+                };
+                scopes.types.insert_operand_ty(self, ty);
+                Ok(Ty::Bool)
+            }
+        }?;
+        scopes.types.insert_ty(self, ty.clone());
+        Ok(ty)
+    }
+}
+
+/// Checks bitwise operators.
+macro_rules! check_bitwise_ops {
+    ($lhs:expr, $rhs:expr, $scopes:expr) => {{
+        let lhs = $lhs.check($scopes)?;
+        let rhs = $rhs.check($scopes)?;
+        unify($scopes, (&**$lhs, lhs), ($rhs, rhs))
+    }};
+}
+
+impl<'ast> Check<'ast> for BitAndExpr {
+    type Out = Ty<'ast>;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let ty = match self {
+            Self::Eq(exp) => exp.check(scopes),
+            Self::BitAndEq(lhs, rhs) => check_bitwise_ops!(lhs, rhs, scopes),
+        }?;
+        scopes.types.insert_ty(self, ty.clone());
+        Ok(ty)
+    }
+}
+
+impl<'ast> Check<'ast> for BitXorExpr {
+    type Out = Ty<'ast>;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let ty = match self {
+            Self::BitAnd(exp) => exp.check(scopes),
+            Self::BitXorBitAnd(lhs, rhs) => check_bitwise_ops!(lhs, rhs, scopes),
+        }?;
+        scopes.types.insert_ty(self, ty.clone());
+        Ok(ty)
+    }
+}
+
+impl<'ast> Check<'ast> for BitOrExpr {
+    type Out = Ty<'ast>;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let ty = match self {
+            Self::BitXor(exp) => exp.check(scopes),
+            Self::BitOrBitXor(lhs, rhs) => check_bitwise_ops!(lhs, rhs, scopes),
+        }?;
+        scopes.types.insert_ty(self, ty.clone());
+        Ok(ty)
+    }
+}
+
+/// Checks logical operators, both operands must be Bools.
+macro_rules! check_logical_ops {
+    ($lhs:expr, $rhs:expr, $scopes:expr) => {{
+        let lhs = $lhs.check($scopes)?;
+        expect($scopes, &**$lhs, &lhs, &Ty::Bool)
+            .map_err(|e| e.at($scopes.file(), &$lhs.span()))?;
+        let rhs = $rhs.check($scopes)?;
+        expect($scopes, $rhs, &rhs, &Ty::Bool).map_err(|e| e.at($scopes.file(), &$rhs.span()))?;
+        Ok(Ty::Bool)
+    }};
+}
+
+impl<'ast> Check<'ast> for LAndExpr {
+    type Out = Ty<'ast>;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let ty = match self {
+            Self::BitOr(exp) => exp.check(scopes),
+            Self::LAndBitOr(lhs, rhs) => check_logical_ops!(lhs, rhs, scopes),
+        }?;
+        scopes.types.insert_ty(self, ty.clone());
+        Ok(ty)
+    }
+}
+
+impl<'ast> Check<'ast> for LOrExpr {
+    type Out = Ty<'ast>;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        let ty = match self {
+            Self::LAnd(exp) => exp.check(scopes),
+            Self::LOrLAnd(lhs, rhs) => check_logical_ops!(lhs, rhs, scopes),
+        }?;
+        scopes.types.insert_ty(self, ty.clone());
+        Ok(ty)
+    }
+}
+
+impl<'ast> Check<'ast> for ConstExpr {
+    type Out = i32;
+
+    fn check(&'ast self, scopes: &mut Scopes<'ast>) -> Result<Self::Out> {
+        self.check_of(&Ty::Int, scopes)
+    }
+}
+
+impl<'ast> ConstExpr {
+    /// Checks the constant expression against the given integer type,
+    /// returns its value.
+    fn check_of(&'ast self, ty: &Ty<'ast>, scopes: &mut Scopes<'ast>) -> Result<i32> {
+        let value = (E0010::E0010 { ast: self }).run(scopes)?;
+        let found = self.exp.check(scopes)?;
+        expect(scopes, &self.exp, &found, ty).map_err(|e| e.at(scopes.file(), &self.pos))?;
+        scopes.types.insert_value(&self.exp, value);
+        Ok(value)
+    }
+}
+
+/// Checks that a value of the given type can be used as an `Int`.
+fn expect_int(ty: &Ty) -> std::result::Result<(), Error> {
+    match ty {
+        Ty::Error | Ty::Int => Ok(()),
+        Ty::Unit => Err(Error::UseVoidValue),
+        Ty::Array(..) => Err(Error::NonIntCalc),
+        Ty::Struct(_) => Err(Error::StructValue),
+        Ty::Pointer(_) => Err(Error::PointerMismatch),
+        Ty::Bool => Err(Error::BoolCalc),
+        Ty::Sized(_) => Err(Error::IntMismatch),
+    }
+}
+
+/// Checks that a value of the given type is an integer of any type.
+fn expect_any_int(ty: &Ty) -> std::result::Result<(), Error> {
+    match ty {
+        Ty::Sized(_) => Ok(()),
+        ty => expect_int(ty),
+    }
+}
+
+/// Checks that the expression is not an integer literal above `i32::MAX`,
+/// which is only allowed where a `U32` value is expected.
+fn not_large_literal(scopes: &Scopes, exp: &impl Span) -> std::result::Result<(), Error> {
+    match scopes.is_large_literal(exp) {
+        true => Err(Error::LiteralTooLarge),
+        false => Ok(()),
+    }
+}
+
+/// Checks that the value of the given expression, which has type `found`,
+/// can be used as a value of type `ty`. Integer constants that fit
+/// can be used as values of fixed-width integer types, and integer
+/// literals above `i32::MAX` can only be used as `U32` values.
+fn expect(
+    scopes: &Scopes,
+    exp: &(impl Evaluate + Span),
+    found: &Ty,
+    ty: &Ty,
+) -> std::result::Result<(), Error> {
+    if !matches!(ty, Ty::Error | Ty::Sized(IntTy::U32)) {
+        not_large_literal(scopes, exp)?;
+    }
+    match (ty, found) {
+        (Ty::Error, _) | (_, Ty::Error) => Ok(()),
+        (_, Ty::Unit) => Err(Error::UseVoidValue),
+        (Ty::Int, found) => expect_int(found),
+        (Ty::Sized(ty), Ty::Sized(found)) if ty == found => Ok(()),
+        (Ty::Sized(ty), Ty::Int) => match exp.eval(scopes) {
+            Some(num) if ty.contains(num) => Ok(()),
+            _ => Err(Error::IntMismatch),
+        },
+        (Ty::Sized(_), Ty::Sized(_)) => Err(Error::IntMismatch),
+        (Ty::Sized(_), found) => expect_int(found),
+        (Ty::Bool, Ty::Bool) => Ok(()),
+        (Ty::Bool, _) => Err(Error::NonBoolValue),
+        (ty, found) if ty.accepts(found) => Ok(()),
+        _ => Err(Error::PointerMismatch),
+    }
+}
+
+/// Checks the operands of a binary operation on integers of the same type,
+/// integer constants take the type of the other operand. Returns the type.
+fn unify<'ast>(
+    scopes: &Scopes<'ast>,
+    (lhs_exp, lhs): (&(impl Evaluate + Span), Ty<'ast>),
+    (rhs_exp, rhs): (&(impl Evaluate + Span), Ty<'ast>),
+) -> Result<Ty<'ast>> {
+    // literals above `i32::MAX` are `U32` values only if the other operand is
+    let ty = match (&lhs, &rhs) {
+        (Ty::Sized(ty), _) if !scopes.is_large_literal(lhs_exp) => Ty::Sized(*ty),
+        (_, Ty::Sized(ty)) if !scopes.is_large_literal(rhs_exp) => Ty::Sized(*ty),
+        _ => Ty::Int,
+    };
+    expect(scopes, lhs_exp, &lhs, &ty).map_err(|e| e.at(scopes.file(), &lhs_exp.span()))?;
+    expect(scopes, rhs_exp, &rhs, &ty).map_err(|e| e.at(scopes.file(), &rhs_exp.span()))?;
+    Ok(ty)
+}
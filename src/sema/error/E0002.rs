@@ -0,0 +1,29 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::error::Result;
+use crate::sema::eval::Evaluate;
+use crate::sema::scopes::Scopes;
+use codespan_reporting::diagnostic::Label;
+
+/// E0002: Fail to evaluate the initializer of a global value
+pub struct E0002<'ast> {
+    pub ast: &'ast ast::Expr,
+}
+
+impl<'ast> E0002<'ast> {
+    pub fn run(&self, scopes: &mut Scopes<'ast>) -> Result<i32> {
+        let value = self.ast.eval(scopes).ok_or_else(|| Error {
+            message: String::from("Unable to initialize constant expression"),
+            code: String::from("E0002"),
+            labels: vec![
+                Label::primary(scopes.file(), self.ast.pos.0..self.ast.pos.1)
+                    .with_message("Illegal constant expression".to_string()),
+            ],
+            note: vec![String::from(
+                "Global variables must be initialized with constant expressions",
+            )],
+        })?;
+        scopes.types.insert_value(self.ast, value);
+        Ok(value)
+    }
+}
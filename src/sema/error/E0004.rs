@@ -0,0 +1,25 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::error::Result;
+use crate::sema::scopes::Scopes;
+use codespan_reporting::diagnostic::Label;
+
+/// E0004: A function whose return value type is Unit cannot have a return value
+pub struct E0004<'ast> {
+    pub ast: &'ast ast::Return,
+}
+
+impl<'ast> E0004<'ast> {
+    pub fn run<Out>(&self, scopes: &Scopes) -> Result<Out> {
+        let exp = self.ast.exp.as_ref().unwrap();
+        Err(Error {
+            message: String::from("Illegal return value statement"),
+            code: String::from("E0004"),
+            labels: vec![Label::primary(scopes.file(), exp.pos.0..exp.pos.1)
+                .with_message("Invalid return value".to_string())],
+            note: vec![String::from(
+                "A function whose return value type is Unit cannot have a return value",
+            )],
+        })
+    }
+}
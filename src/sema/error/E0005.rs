@@ -0,0 +1,31 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::error::Result;
+use crate::sema::error::E0026::E0026;
+use crate::sema::scopes::Scopes;
+use codespan_reporting::diagnostic::Label;
+
+/// E0005: Continue statement is not in a loop
+pub struct E0005<'ast> {
+    pub ast: &'ast ast::Continue,
+}
+
+impl<'ast> E0005<'ast> {
+    pub fn run(&self, scopes: &Scopes<'ast>) -> Result<()> {
+        if let Some(label) = &self.ast.label {
+            return E0026 { ast: label }.run(scopes);
+        }
+        match scopes.loop_label(None) {
+            Some(_) => Ok(()),
+            None => Err(Error {
+                message: String::from("The `continue` statement is not in the loop"),
+                code: String::from("E0005"),
+                labels: vec![
+                    Label::primary(scopes.file(), self.ast.pos.0..self.ast.pos.1)
+                        .with_message("This is not within a loop statement".to_string()),
+                ],
+                note: vec![String::from("`continue` must be within a loop statement")],
+            }),
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::error::Result;
+use crate::sema::scopes::Scopes;
+use codespan_reporting::diagnostic::Label;
+
+/// E0006: Cannot deference constant as an integer
+pub struct E0006<'ast> {
+    pub ast: &'ast ast::LVal,
+}
+
+impl<'ast> E0006<'ast> {
+    pub fn run(&self, scopes: &Scopes<'ast>) -> Result<()> {
+        if self.ast.derefs == 0 && self.ast.indices.is_empty() && self.ast.fields.is_empty() {
+            Ok(())
+        } else {
+            Err(Error {
+                message: String::from("Cannot deference a constant as an integer"),
+                code: String::from("E0006"),
+                labels: vec![
+                    Label::primary(scopes.file(), self.ast.pos.0..self.ast.pos.1)
+                        .with_message("This is an integer".to_string()),
+                ],
+                note: vec![String::from("Only pointers can be dereferenced")],
+            })
+        }
+    }
+}
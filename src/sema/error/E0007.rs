@@ -0,0 +1,28 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::error::Result;
+use crate::sema::scopes::Scopes;
+use codespan_reporting::diagnostic::Label;
+
+/// E0007: Cannot deference an array as an integer
+pub struct E0007<'ast> {
+    pub ast: &'ast ast::LVal,
+}
+
+impl<'ast> E0007<'ast> {
+    pub fn run(&self, scopes: &Scopes, dims: usize) -> Result<()> {
+        if dims == 0 {
+            Err(Error {
+                message: String::from("Cannot deference an array as an integer"),
+                code: String::from("E0007"),
+                labels: vec![
+                    Label::primary(scopes.file(), self.ast.pos.0..self.ast.pos.1)
+                        .with_message("This is an integer".to_string()),
+                ],
+                note: vec![String::from("Only pointers can be dereferenced")],
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::error::Result;
+use crate::sema::scopes::Scopes;
+use crate::sema::Ty;
+use codespan_reporting::diagnostic::Label;
+
+/// E0008: Missing parameters
+pub struct E0008<'ast> {
+    pub ast: &'ast ast::FuncCall,
+}
+
+impl<'ast> E0008<'ast> {
+    pub fn run(&self, scopes: &Scopes, params: &[Ty], args: &[Ty]) -> Result<()> {
+        if params.len() != args.len() {
+            Err(Error {
+                message: String::from("Missing parameters"),
+                code: String::from("E0008"),
+                labels: vec![Label::primary(scopes.file(), self.ast.pos.0..self.ast.pos.1)
+                    .with_message(format!(
+                        "expected {} arguments, found {}",
+                        params.len(),
+                        args.len()
+                    ))],
+                note: vec![String::from("The parameters of the function call do not match the actual number of parameters")],
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::error::Result;
+use crate::sema::scopes::Scopes;
+use crate::sema::Ty;
+use codespan_reporting::diagnostic::Label;
+
+/// E0009: Wrong parameter type
+pub struct E0009<'ast> {
+    pub ast: &'ast ast::FuncCall,
+}
+
+impl<'ast> E0009<'ast> {
+    pub fn run(&self, scopes: &Scopes, params: &[Ty], args: &[Ty]) -> Result<()> {
+        // integer constants that fit have already been given the types of the parameters
+        let params = params.iter().zip(args);
+        for ((param, arg), exp) in params.zip(&self.ast.args) {
+            if !param.accepts(arg) {
+                return Err(Error {
+                    message: String::from("Wrong parameter type"),
+                    code: String::from("E0009"),
+                    labels: vec![Label::primary(scopes.file(), exp.pos.0..exp.pos.1)
+                        .with_message(format!("expect: {}, found: {}", param, arg))],
+                    note: vec![String::from("The parameter type of the function call does not match the actual parameter type")],
+                });
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::error::Result;
+use crate::sema::eval::Evaluate;
+use crate::sema::scopes::Scopes;
+use codespan_reporting::diagnostic::Label;
+
+/// E0010: Fail to evaluate a constant expression
+pub struct E0010<'ast> {
+    pub ast: &'ast ast::ConstExpr,
+}
+
+impl<'ast> E0010<'ast> {
+    pub fn run(&self, scopes: &Scopes<'ast>) -> Result<i32> {
+        self.ast.eval(scopes).ok_or_else(|| Error {
+            message: String::from("Unable to initialize constant expression"),
+            code: String::from("E0010"),
+            labels: vec![
+                Label::primary(scopes.file(), self.ast.pos.0..self.ast.pos.1)
+                    .with_message("Illegal constant expression".to_string()),
+            ],
+            note: vec![String::from(
+                "Only constants and literals can appear in constant expressions, \
+                 and `I64` values are never constant",
+            )],
+        })
+    }
+}
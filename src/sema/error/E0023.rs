@@ -0,0 +1,38 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::error::Result;
+use crate::sema::scopes::Scopes;
+use codespan_reporting::diagnostic::Label;
+
+/// E0023: The declared type of a constant does not match its value
+pub struct E0023<'ast> {
+    pub ast: &'ast ast::ConstDef,
+}
+
+impl<'ast> E0023<'ast> {
+    pub fn run(&self, scopes: &Scopes) -> Result<()> {
+        match self.ast.ty {
+            ast::Type::Int(_) | ast::Type::Sized(..) => Ok(()),
+            _ => {
+                let value_pos = match &self.ast.init {
+                    ast::ConstInitVal::Expr(exp) => &exp.pos,
+                    ast::ConstInitVal::List(_) => &self.ast.pos,
+                };
+                let ty_pos = self.ast.ty.pos();
+                Err(Error {
+                    message: String::from("Mismatched types"),
+                    code: String::from("E0023"),
+                    labels: vec![
+                        Label::primary(scopes.file(), value_pos.0..value_pos.1)
+                            .with_message(format!("expected `{}`, found `Int`", self.ast.ty)),
+                        Label::secondary(scopes.file(), ty_pos.0..ty_pos.1)
+                            .with_message("expected due to this type".to_string()),
+                    ],
+                    note: vec![String::from(
+                        "The value of a constant must have its declared type",
+                    )],
+                })
+            }
+        }
+    }
+}
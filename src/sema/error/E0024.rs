@@ -0,0 +1,28 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::error::Result;
+use crate::sema::scopes::Scopes;
+use codespan_reporting::diagnostic::Label;
+
+/// E0024: A variable can not be declared with type Unit
+pub struct E0024<'ast> {
+    pub ast: &'ast ast::VarDef,
+}
+
+impl<'ast> E0024<'ast> {
+    pub fn run(&self, scopes: &Scopes) -> Result<()> {
+        match &self.ast.ty {
+            Some(ty @ ast::Type::Unit(pos)) => Err(Error {
+                message: String::from("Invalid variable type"),
+                code: String::from("E0024"),
+                labels: vec![Label::primary(scopes.file(), pos.0..pos.1)
+                    .with_message(format!("`{}` can not have type `{}`", self.ast.id.name, ty))],
+                note: vec![String::from(
+                    "Variables hold values, so they can have type Int, Bool, a fixed-width \
+                     integer type, a struct type or a pointer type, but not Unit",
+                )],
+            }),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::error::Result;
+use crate::sema::scopes::Scopes;
+use codespan_reporting::diagnostic::Label;
+
+/// E0026: Use of an undeclared loop label
+pub struct E0026<'ast> {
+    pub ast: &'ast ast::Label,
+}
+
+impl<'ast> E0026<'ast> {
+    pub fn run(&self, scopes: &Scopes<'ast>) -> Result<()> {
+        let name = &self.ast.name;
+        match scopes.loop_label(Some(name)) {
+            Some(_) => Ok(()),
+            None => Err(Error {
+                message: format!("Use of undeclared label `'{}`", name),
+                code: String::from("E0026"),
+                labels: vec![
+                    Label::primary(scopes.file(), self.ast.pos.0..self.ast.pos.1)
+                        .with_message(format!("no enclosing loop is labeled `'{}`", name)),
+                ],
+                note: vec![String::from(
+                    "`break` and `continue` can only refer to the labels of enclosing loops",
+                )],
+            }),
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::error::Result;
+use crate::sema::scopes::Scopes;
+use codespan_reporting::diagnostic::Label;
+
+/// E0027: Loop label shadows a label of an enclosing loop
+pub struct E0027<'ast> {
+    pub ast: &'ast ast::Label,
+}
+
+impl<'ast> E0027<'ast> {
+    pub fn run(&self, scopes: &Scopes<'ast>) -> Result<()> {
+        let name = &self.ast.name;
+        match scopes.loop_label(Some(name)).flatten() {
+            Some(outer) => Err(Error {
+                message: format!("Label `'{}` shadows a label that is already in scope", name),
+                code: String::from("E0027"),
+                labels: vec![
+                    Label::primary(scopes.file(), self.ast.pos.0..self.ast.pos.1)
+                        .with_message("shadows the label of an enclosing loop"),
+                    Label::secondary(scopes.file(), outer.pos.0..outer.pos.1)
+                        .with_message("first declared here"),
+                ],
+                note: vec![String::from("rename one of the labels")],
+            }),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::error::Result;
+use crate::sema::flow::ControlFlow;
+use crate::sema::scopes::Scopes;
+use codespan_reporting::diagnostic::Label;
+
+/// E0028: Function with a return value may reach its end without returning
+pub struct E0028<'ast> {
+    pub ast: &'ast ast::FuncDef,
+}
+
+impl<'ast> E0028<'ast> {
+    pub fn run(&self, scopes: &Scopes) -> Result<()> {
+        let ty = match &self.ast.ty {
+            ast::Type::Unit(_) | ast::Type::Struct(_) => return Ok(()),
+            ty => ty,
+        };
+        if !self.ast.block.falls_through(scopes) {
+            return Ok(());
+        }
+        let end = self.ast.block.pos.1;
+        Err(Error {
+            message: String::from("Missing return value"),
+            code: String::from("E0028"),
+            labels: vec![
+                Label::primary(scopes.file(), end - 1..end)
+                    .with_message("the execution may reach the end of the function here"),
+                Label::secondary(scopes.file(), ty.pos().0..ty.pos().1)
+                    .with_message(format!("expected `{}` because of this return type", ty)),
+            ],
+            note: vec![String::from(
+                "A function whose return value type is not Unit must return a value on every path",
+            )],
+        })
+    }
+}
@@ -0,0 +1,36 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::error::Result;
+use crate::sema::scopes::Scopes;
+use crate::sema::Ty;
+use codespan_reporting::diagnostic::Label;
+
+/// E0029: Arms of an `if` expression have incompatible types
+pub struct E0029<'ast> {
+    pub ast: &'ast ast::IfExpr,
+}
+
+impl<'ast> E0029<'ast> {
+    pub fn run(&self, scopes: &Scopes, then: &Ty, else_then: &Ty) -> Result<()> {
+        // arms that failed to check have been reported
+        let failed = *then == Ty::Error || *else_then == Ty::Error;
+        if failed || (*then == Ty::Unit) == (*else_then == Ty::Unit) {
+            return Ok(());
+        }
+        let then_pos = &self.ast.then.value.pos;
+        let else_pos = self.ast.else_then.value_pos();
+        Err(Error {
+            message: String::from("`if` and `else` have incompatible types"),
+            code: String::from("E0029"),
+            labels: vec![
+                Label::primary(scopes.file(), else_pos.0..else_pos.1)
+                    .with_message(format!("expected `{}`, found `{}`", then, else_then)),
+                Label::secondary(scopes.file(), then_pos.0..then_pos.1)
+                    .with_message("expected because of this".to_string()),
+            ],
+            note: vec![String::from(
+                "Both arms of an `if` expression must have a value or both be `Unit`",
+            )],
+        })
+    }
+}
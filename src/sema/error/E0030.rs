@@ -0,0 +1,28 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::error::Result;
+use crate::sema::scopes::Scopes;
+use codespan_reporting::diagnostic::Label;
+
+/// E0030: Match statement is not exhaustive
+pub struct E0030<'ast> {
+    pub ast: &'ast ast::Match,
+}
+
+impl<'ast> E0030<'ast> {
+    pub fn run(&self, scopes: &Scopes) -> Result<()> {
+        if self.ast.arms.iter().any(|arm| arm.is_wildcard()) {
+            return Ok(());
+        }
+        let exp = &self.ast.exp.pos;
+        Err(Error {
+            message: String::from("Non-exhaustive patterns"),
+            code: String::from("E0030"),
+            labels: vec![Label::primary(scopes.file(), exp.0..exp.1)
+                .with_message("pattern `_` not covered".to_string())],
+            note: vec![String::from(
+                "Matching an Int requires a wildcard arm `_ => ...`",
+            )],
+        })
+    }
+}
@@ -0,0 +1,29 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::error::Result;
+use crate::sema::scopes::Scopes;
+use codespan_reporting::diagnostic::Label;
+
+/// E0031: Range pattern is empty
+pub struct E0031<'ast> {
+    pub lo: &'ast ast::PatValue,
+    pub hi: &'ast ast::PatValue,
+}
+
+impl<'ast> E0031<'ast> {
+    pub fn run(&self, scopes: &Scopes, lo: i32, hi: i32) -> Result<()> {
+        if lo <= hi {
+            return Ok(());
+        }
+        let (start, end) = (self.lo.pos().0, self.hi.pos().1);
+        Err(Error {
+            message: String::from("Empty range pattern"),
+            code: String::from("E0031"),
+            labels: vec![Label::primary(scopes.file(), start..end)
+                .with_message(format!("`{}..={}` matches no value", lo, hi))],
+            note: vec![String::from(
+                "The lower bound of a range pattern must not be greater than the upper bound",
+            )],
+        })
+    }
+}
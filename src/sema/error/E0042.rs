@@ -0,0 +1,32 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::check::Check;
+use crate::sema::error::{self, Result};
+use crate::sema::scopes::Scopes;
+use crate::sema::Ty;
+use codespan_reporting::diagnostic::Label;
+
+/// E0042: Condition of an `if` or a loop is not a Bool
+pub struct E0042<'ast> {
+    pub ast: &'ast ast::Expr,
+}
+
+impl<'ast> E0042<'ast> {
+    pub fn run(&self, scopes: &mut Scopes<'ast>) -> Result<()> {
+        match self.ast.check(scopes)? {
+            Ty::Bool | Ty::Error => Ok(()),
+            Ty::Unit => Err(error::Error::UseVoidValue.at(scopes.file(), &self.ast.pos)),
+            ty => Err(Error {
+                message: String::from("Condition is not a Bool"),
+                code: String::from("E0042"),
+                labels: vec![
+                    Label::primary(scopes.file(), self.ast.pos.0..self.ast.pos.1)
+                        .with_message(format!("expected `Bool`, found `{}`", ty)),
+                ],
+                note: vec![String::from(
+                    "Compare the value explicitly (`x != 0`), or convert it with `as Bool`",
+                )],
+            }),
+        }
+    }
+}
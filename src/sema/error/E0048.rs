@@ -0,0 +1,27 @@
+use crate::ast;
+use crate::error::Error;
+use crate::sema::error::Result;
+use crate::sema::scopes::Scopes;
+use crate::sema::Ty;
+use codespan_reporting::diagnostic::Label;
+
+/// E0048: A function with a return value returns without a value
+pub struct E0048<'ast> {
+    pub ast: &'ast ast::Return,
+}
+
+impl<'ast> E0048<'ast> {
+    pub fn run(&self, scopes: &Scopes, ty: &Ty) -> Result<()> {
+        Err(Error {
+            message: String::from("Return statement without a value"),
+            code: String::from("E0048"),
+            labels: vec![
+                Label::primary(scopes.file(), self.ast.pos.0..self.ast.pos.1)
+                    .with_message(format!("expected a value of type `{}`", ty)),
+            ],
+            note: vec![String::from(
+                "A function whose return value type is not Unit must return a value",
+            )],
+        })
+    }
+}
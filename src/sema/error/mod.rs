@@ -0,0 +1,163 @@
+#![allow(non_snake_case)]
+
+pub mod E0002;
+pub mod E0003;
+pub mod E0004;
+pub mod E0005;
+pub mod E0006;
+pub mod E0007;
+pub mod E0008;
+pub mod E0009;
+pub mod E0010;
+pub mod E0023;
+pub mod E0024;
+pub mod E0026;
+pub mod E0027;
+pub mod E0028;
+pub mod E0029;
+pub mod E0030;
+pub mod E0031;
+pub mod E0042;
+pub mod E0048;
+
+use crate::ast::Position;
+use crate::error::{self, FileId};
+use codespan_reporting::diagnostic::Label;
+
+/// Result type of the checks.
+pub type Result<T> = std::result::Result<T, error::Error>;
+
+/// Possible errors of the checks that are detected without access to the AST.
+#[derive(Debug)]
+pub enum Error {
+    DuplicatedDef,
+    SymbolNotFound,
+    FailedToEval,
+    InvalidArrayLen,
+    InvalidInit,
+    ArrayAssign,
+    UseVoidValue,
+    NonIntCalc,
+    ConstAssign,
+    UnsupportedStruct,
+    StructValue,
+    FieldNotFound,
+    UnsupportedPointer,
+    PointerMismatch,
+    NonPointerDeref,
+    AddrOfValue,
+    BoolCalc,
+    NonBoolValue,
+    UnsupportedBool,
+    InvalidCast,
+    IntMismatch,
+    UnsupportedSized,
+    LiteralTooLarge,
+    I64Division,
+}
+
+impl Error {
+    /// Returns the code, message and label message of the error.
+    fn describe(&self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            Self::DuplicatedDef => ("E0011", "Duplicated definition", "defined more than once"),
+            Self::SymbolNotFound => ("E0012", "Symbol not found", "not found in this scope"),
+            Self::FailedToEval => (
+                "E0010",
+                "Unable to initialize constant expression",
+                "Illegal constant expression",
+            ),
+            Self::InvalidArrayLen => ("E0013", "Invalid array length", "must be at least 1"),
+            Self::InvalidInit => (
+                "E0014",
+                "Invalid initializer",
+                "does not match the shape of the declared type",
+            ),
+            Self::ArrayAssign => ("E0015", "Cannot assign to an array", "this is an array"),
+            Self::UseVoidValue => ("E0016", "Use of a Unit value", "this has type Unit"),
+            Self::NonIntCalc => ("E0017", "Array used as an integer", "this is an array"),
+            Self::ConstAssign => ("E0025", "Cannot assign to a constant", "this is a constant"),
+            Self::UnsupportedStruct => (
+                "E0032",
+                "Unsupported use of a struct type",
+                "structs can only be passed by pointer",
+            ),
+            Self::StructValue => ("E0033", "Struct used as a value", "this is a struct"),
+            Self::FieldNotFound => ("E0034", "No such field", "no field with this name"),
+            Self::UnsupportedPointer => (
+                "E0035",
+                "Unsupported use of a pointer type",
+                "arrays of pointers can not be initialized, and array parameters of pointers have one dimension",
+            ),
+            Self::PointerMismatch => (
+                "E0036",
+                "Mismatched pointer types",
+                "pointers can not be mixed with integers or other pointer types",
+            ),
+            Self::NonPointerDeref => (
+                "E0037",
+                "Dereference of a non-pointer value",
+                "this is not a pointer",
+            ),
+            Self::AddrOfValue => (
+                "E0038",
+                "Cannot take the address of a value",
+                "only integers, Bools, structs and pointers have addresses",
+            ),
+            Self::BoolCalc => ("E0040", "Bool used as an integer", "this is a Bool"),
+            Self::NonBoolValue => ("E0041", "Expected a Bool value", "this is not a Bool"),
+            Self::UnsupportedBool => (
+                "E0043",
+                "Unsupported use of the Bool type",
+                "Bools can only be stored in variables and parameters",
+            ),
+            Self::InvalidCast => (
+                "E0044",
+                "Invalid conversion",
+                "only conversions between integers and Bools are supported",
+            ),
+            Self::IntMismatch => (
+                "E0045",
+                "Mismatched integer types",
+                "integers of different types must be converted with `as`",
+            ),
+            Self::UnsupportedSized => (
+                "E0047",
+                "Unsupported use of a fixed-width integer type",
+                "arrays of fixed-width integers can only be variables, struct fields and parameters with one dimension",
+            ),
+            Self::LiteralTooLarge => (
+                "E0022",
+                "Invalid literal",
+                "integer literal is too large, only `U32` values can be above `2147483647`",
+            ),
+            Self::I64Division => (
+                "E0046",
+                "Unsupported operation on `I64` values",
+                "`I64` values can not be divided, only `+`, `-`, `*`, bitwise operators, shifts and comparisons are supported",
+            ),
+        }
+    }
+
+    /// Converts the error into a diagnostic labeled at the given position.
+    pub fn at(self, file: FileId, pos: &Position) -> error::Error {
+        let (_, _, label) = self.describe();
+        let mut error = error::Error::from(self);
+        error
+            .labels
+            .push(Label::primary(file, pos.0..pos.1).with_message(label));
+        error
+    }
+}
+
+impl From<Error> for error::Error {
+    fn from(error: Error) -> Self {
+        let (code, message, _) = error.describe();
+        error::Error {
+            message: String::from(message),
+            code: String::from(code),
+            labels: Vec::new(),
+            note: Vec::new(),
+        }
+    }
+}
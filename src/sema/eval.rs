@@ -0,0 +1,227 @@
+use super::scopes::{Scopes, Symbol};
+use super::Ty;
+use crate::ast::*;
+
+/// Trait for evaluating constant.
+pub trait Evaluate {
+    fn eval(&self, scopes: &Scopes) -> Option<i32>;
+}
+
+impl Evaluate for Expr {
+    fn eval(&self, scopes: &Scopes) -> Option<i32> {
+        self.lor.eval(scopes)
+    }
+}
+
+impl Evaluate for LVal {
+    fn eval(&self, scopes: &Scopes) -> Option<i32> {
+        let LValBase::Var(id) = &self.base else {
+            return None;
+        };
+        let val = scopes.value(&id.name).ok()?;
+        if self.derefs == 0 && self.indices.is_empty() && self.fields.is_empty() {
+            // constants are evaluated as words, so `I64` constants are not evaluated
+            match val {
+                Symbol::Const(_, Ty::Sized(IntTy::I64)) => None,
+                Symbol::Const(i, _) => Some(*i),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+}
+
+impl Evaluate for PrimaryExpr {
+    fn eval(&self, scopes: &Scopes) -> Option<i32> {
+        match self {
+            Self::Expr(exp) => exp.eval(scopes),
+            Self::LVal(lval) => lval.eval(scopes),
+            Self::Number(num) => Some(num.value),
+            Self::Bool(b) => Some(b.value as i32),
+            Self::Str(_) => None,
+            Self::If(exp) => exp.eval(scopes),
+        }
+    }
+}
+
+impl Evaluate for IfExpr {
+    fn eval(&self, scopes: &Scopes) -> Option<i32> {
+        if self.cond.eval(scopes)? != 0 {
+            self.then.eval(scopes)
+        } else {
+            match &self.else_then {
+                ElseExpr::Block(block) => block.eval(scopes),
+                ElseExpr::If(exp) => exp.eval(scopes),
+            }
+        }
+    }
+}
+
+impl Evaluate for ValueBlock {
+    fn eval(&self, scopes: &Scopes) -> Option<i32> {
+        // only blocks without statements can be constant
+        if self.items.is_empty() {
+            self.value.eval(scopes)
+        } else {
+            None
+        }
+    }
+}
+
+impl Evaluate for UnaryExpr {
+    fn eval(&self, scopes: &Scopes) -> Option<i32> {
+        match self {
+            Self::Primary(primary) => primary.eval(scopes),
+            Self::Call(_) | Self::AddrOf(_) => None,
+            Self::Unary(op, exp) => exp.eval(scopes).map(|exp| match op {
+                UnaryOp::Neg(_) => exp.wrapping_neg(),
+                UnaryOp::LNot(_) => (exp == 0) as i32,
+                UnaryOp::Not(_) => !exp,
+            }),
+            Self::Cast(exp, ty) => match ty {
+                Type::Int(_) => exp.eval(scopes),
+                Type::Bool(_) => exp.eval(scopes).map(|exp| (exp != 0) as i32),
+                Type::Sized(IntTy::I64, _) => None,
+                Type::Sized(ty, _) => exp.eval(scopes).map(|exp| ty.wrap(exp)),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl Evaluate for MulExpr {
+    fn eval(&self, scopes: &Scopes) -> Option<i32> {
+        match self {
+            Self::Unary(exp) => exp.eval(scopes),
+            Self::MulUnary(lhs, op, rhs) => match (lhs.eval(scopes), rhs.eval(scopes)) {
+                (Some(lhs), Some(rhs)) => match op {
+                    MulOp::Mul(_) => Some(lhs.wrapping_mul(rhs)),
+                    MulOp::Div(_) => (rhs != 0).then(|| lhs.wrapping_div(rhs)),
+                    MulOp::Mod(_) => (rhs != 0).then(|| lhs.wrapping_rem(rhs)),
+                },
+                _ => None,
+            },
+        }
+    }
+}
+
+impl Evaluate for AddExpr {
+    fn eval(&self, scopes: &Scopes) -> Option<i32> {
+        match self {
+            Self::Mul(exp) => exp.eval(scopes),
+            Self::AddMul(lhs, op, rhs) => match (lhs.eval(scopes), rhs.eval(scopes)) {
+                (Some(lhs), Some(rhs)) => Some(match op {
+                    AddOp::Add(_) => lhs.wrapping_add(rhs),
+                    AddOp::Sub(_) => lhs.wrapping_sub(rhs),
+                }),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl Evaluate for ShiftExpr {
+    fn eval(&self, scopes: &Scopes) -> Option<i32> {
+        match self {
+            Self::Add(exp) => exp.eval(scopes),
+            Self::ShiftAdd(lhs, op, rhs) => match (lhs.eval(scopes), rhs.eval(scopes)) {
+                (Some(lhs), Some(rhs)) => Some(match op {
+                    ShiftOp::Shl(_) => lhs.wrapping_shl(rhs as u32),
+                    ShiftOp::Sar(_) => lhs.wrapping_shr(rhs as u32),
+                    ShiftOp::Shr(_) => (lhs as u32).wrapping_shr(rhs as u32) as i32,
+                }),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl Evaluate for RelExpr {
+    fn eval(&self, scopes: &Scopes) -> Option<i32> {
+        match self {
+            Self::Shift(exp) => exp.eval(scopes),
+            Self::RelShift(lhs, op, rhs) => match (lhs.eval(scopes), rhs.eval(scopes)) {
+                (Some(lhs), Some(rhs)) => Some(match op {
+                    RelOp::Lt(_) => (lhs < rhs) as i32,
+                    RelOp::Gt(_) => (lhs > rhs) as i32,
+                    RelOp::Le(_) => (lhs <= rhs) as i32,
+                    RelOp::Ge(_) => (lhs >= rhs) as i32,
+                }),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl Evaluate for EqExpr {
+    fn eval(&self, scopes: &Scopes) -> Option<i32> {
+        match self {
+            Self::Rel(exp) => exp.eval(scopes),
+            Self::EqRel(lhs, op, rhs) => match (lhs.eval(scopes), rhs.eval(scopes)) {
+                (Some(lhs), Some(rhs)) => Some(match op {
+                    EqOp::Eq(_) => (lhs == rhs) as i32,
+                    EqOp::Ne(_) => (lhs != rhs) as i32,
+                }),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl Evaluate for BitAndExpr {
+    fn eval(&self, scopes: &Scopes) -> Option<i32> {
+        match self {
+            Self::Eq(exp) => exp.eval(scopes),
+            Self::BitAndEq(lhs, rhs) => Some(lhs.eval(scopes)? & rhs.eval(scopes)?),
+        }
+    }
+}
+
+impl Evaluate for BitXorExpr {
+    fn eval(&self, scopes: &Scopes) -> Option<i32> {
+        match self {
+            Self::BitAnd(exp) => exp.eval(scopes),
+            Self::BitXorBitAnd(lhs, rhs) => Some(lhs.eval(scopes)? ^ rhs.eval(scopes)?),
+        }
+    }
+}
+
+impl Evaluate for BitOrExpr {
+    fn eval(&self, scopes: &Scopes) -> Option<i32> {
+        match self {
+            Self::BitXor(exp) => exp.eval(scopes),
+            Self::BitOrBitXor(lhs, rhs) => Some(lhs.eval(scopes)? | rhs.eval(scopes)?),
+        }
+    }
+}
+
+impl Evaluate for LAndExpr {
+    fn eval(&self, scopes: &Scopes) -> Option<i32> {
+        match self {
+            Self::BitOr(exp) => exp.eval(scopes),
+            Self::LAndBitOr(lhs, rhs) => match (lhs.eval(scopes), rhs.eval(scopes)) {
+                (Some(lhs), Some(rhs)) => Some((lhs != 0 && rhs != 0) as i32),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl Evaluate for LOrExpr {
+    fn eval(&self, scopes: &Scopes) -> Option<i32> {
+        match self {
+            Self::LAnd(exp) => exp.eval(scopes),
+            Self::LOrLAnd(lhs, rhs) => match (lhs.eval(scopes), rhs.eval(scopes)) {
+                (Some(lhs), Some(rhs)) => Some((lhs != 0 || rhs != 0) as i32),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl Evaluate for ConstExpr {
+    fn eval(&self, scopes: &Scopes) -> Option<i32> {
+        self.exp.eval(scopes)
+    }
+}
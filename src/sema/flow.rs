@@ -0,0 +1,269 @@
+use super::eval::Evaluate;
+use super::scopes::Scopes;
+use crate::ast::*;
+
+/// Kinds of jump that leave the body of a loop.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Jump {
+    Break,
+    Continue,
+}
+
+/// Trait for control flow analysis.
+pub trait ControlFlow {
+    /// Returns `true` if the execution may reach the end of the node.
+    /// Expressions are assumed to always reach their end.
+    fn falls_through(&self, _: &Scopes) -> bool {
+        true
+    }
+
+    /// Returns `true` if the node contains a jump of the given kind
+    /// to the loop with the given label, `inner` is `true` if the node
+    /// is in a loop nested in that loop.
+    fn jumps(&self, jump: Jump, label: Option<&str>, inner: bool) -> bool;
+}
+
+/// Returns the name of the given loop label.
+fn name(label: &Option<Label>) -> Option<&str> {
+    label.as_ref().map(|l| l.name.as_str())
+}
+
+/// Returns `true` if the loop body jumps out of the loop with the given label.
+fn breaks(body: &impl ControlFlow, label: &Option<Label>) -> bool {
+    body.jumps(Jump::Break, name(label), false)
+}
+
+/// Returns `true` unless the condition is a constant true value.
+fn may_be_false(cond: &Expr, scopes: &Scopes) -> bool {
+    cond.eval(scopes).is_none_or(|c| c == 0)
+}
+
+impl<T: ControlFlow> ControlFlow for Option<T> {
+    fn falls_through(&self, scopes: &Scopes) -> bool {
+        self.as_ref().is_none_or(|t| t.falls_through(scopes))
+    }
+
+    fn jumps(&self, jump: Jump, label: Option<&str>, inner: bool) -> bool {
+        self.as_ref().is_some_and(|t| t.jumps(jump, label, inner))
+    }
+}
+
+impl<T: ControlFlow> ControlFlow for [T] {
+    fn falls_through(&self, scopes: &Scopes) -> bool {
+        self.iter().all(|t| t.falls_through(scopes))
+    }
+
+    fn jumps(&self, jump: Jump, label: Option<&str>, inner: bool) -> bool {
+        self.iter().any(|t| t.jumps(jump, label, inner))
+    }
+}
+
+impl ControlFlow for Block {
+    fn falls_through(&self, scopes: &Scopes) -> bool {
+        self.items.falls_through(scopes)
+    }
+
+    fn jumps(&self, jump: Jump, label: Option<&str>, inner: bool) -> bool {
+        self.items.jumps(jump, label, inner)
+    }
+}
+
+impl ControlFlow for BlockItem {
+    fn falls_through(&self, scopes: &Scopes) -> bool {
+        match self {
+            Self::Decl(_) => true,
+            Self::Stmt(stmt) => stmt.falls_through(scopes),
+        }
+    }
+
+    fn jumps(&self, jump: Jump, label: Option<&str>, inner: bool) -> bool {
+        match self {
+            // constant initializers can not jump
+            Self::Decl(Decl::Const(_)) => false,
+            Self::Decl(Decl::Var(decl)) => decl
+                .defs
+                .iter()
+                .any(|def| def.init.jumps(jump, label, inner)),
+            Self::Stmt(stmt) => stmt.jumps(jump, label, inner),
+        }
+    }
+}
+
+impl ControlFlow for InitVal {
+    fn jumps(&self, jump: Jump, label: Option<&str>, inner: bool) -> bool {
+        match self {
+            Self::Expr(exp) => exp.jumps(jump, label, inner),
+            Self::List(list) => list.jumps(jump, label, inner),
+        }
+    }
+}
+
+impl ControlFlow for Stmt {
+    fn falls_through(&self, scopes: &Scopes) -> bool {
+        match self {
+            Self::Block(block) => block.falls_through(scopes),
+            Self::If(s) => match &s.else_then {
+                Some(else_then) => s.then.falls_through(scopes) || else_then.falls_through(scopes),
+                None => true,
+            },
+            // loops with a constant true condition only end with a `break`
+            Self::While(s) => may_be_false(&s.cond, scopes) || breaks(&s.body, &s.label),
+            Self::For(s) => {
+                s.cond
+                    .as_ref()
+                    .is_some_and(|cond| may_be_false(cond, scopes))
+                    || breaks(&s.body, &s.label)
+            }
+            Self::Loop(s) => breaks(&s.body, &s.label),
+            Self::DoWhile(s) => {
+                let reaches_cond = s.body.falls_through(scopes)
+                    || s.body.jumps(Jump::Continue, name(&s.label), false);
+                (reaches_cond && may_be_false(&s.cond, scopes)) || breaks(&s.body, &s.label)
+            }
+            // without a wildcard arm the execution may skip all arms
+            Self::Match(s) => {
+                !s.arms.iter().any(|arm| arm.is_wildcard())
+                    || s.arms.iter().any(|arm| arm.body.falls_through(scopes))
+            }
+            Self::Break(_) | Self::Continue(_) | Self::Return(_) => false,
+            // the syntax error has been reported, a missing return may be a consequence of it
+            Self::Error(_) => false,
+            _ => true,
+        }
+    }
+
+    fn jumps(&self, jump: Jump, label: Option<&str>, inner: bool) -> bool {
+        let targets = |l: &Option<Label>| match l {
+            Some(l) => Some(l.name.as_str()) == label,
+            None => !inner,
+        };
+        // only loop bodies are nested in the loop, other parts of
+        // the loop statement are generated outside of it
+        match self {
+            Self::Assign(s) => s.lval.jumps(jump, label, inner) || s.exp.jumps(jump, label, inner),
+            Self::CompoundAssign(s) => {
+                s.lval.jumps(jump, label, inner) || s.exp.jumps(jump, label, inner)
+            }
+            Self::Increment(s) => s.lval.jumps(jump, label, inner),
+            Self::ExprStmt(s) => s.exp.jumps(jump, label, inner),
+            Self::Block(block) => block.jumps(jump, label, inner),
+            Self::If(s) => {
+                s.cond.jumps(jump, label, inner)
+                    || s.then.jumps(jump, label, inner)
+                    || s.else_then.jumps(jump, label, inner)
+            }
+            Self::While(s) => s.cond.jumps(jump, label, inner) || s.body.jumps(jump, label, true),
+            Self::For(s) => {
+                s.init.jumps(jump, label, inner)
+                    || s.cond.jumps(jump, label, inner)
+                    || s.step.jumps(jump, label, inner)
+                    || s.body.jumps(jump, label, true)
+            }
+            Self::ForRange(s) => {
+                s.start.jumps(jump, label, inner)
+                    || s.end.jumps(jump, label, inner)
+                    || s.body.jumps(jump, label, true)
+            }
+            Self::Loop(s) => s.body.jumps(jump, label, true),
+            Self::DoWhile(s) => s.cond.jumps(jump, label, inner) || s.body.jumps(jump, label, true),
+            Self::Match(s) => {
+                s.exp.jumps(jump, label, inner)
+                    || s.arms.iter().any(|arm| arm.body.jumps(jump, label, inner))
+            }
+            Self::Break(s) => jump == Jump::Break && targets(&s.label),
+            Self::Continue(s) => jump == Jump::Continue && targets(&s.label),
+            Self::Return(s) => s.exp.jumps(jump, label, inner),
+            Self::Error(_) => false,
+        }
+    }
+}
+
+impl ControlFlow for Expr {
+    fn jumps(&self, jump: Jump, label: Option<&str>, inner: bool) -> bool {
+        self.lor.jumps(jump, label, inner)
+    }
+}
+
+impl ControlFlow for LVal {
+    fn jumps(&self, jump: Jump, label: Option<&str>, inner: bool) -> bool {
+        let base = match &self.base {
+            LValBase::Var(_) => false,
+            LValBase::Expr(exp) => exp.jumps(jump, label, inner),
+        };
+        base || self.indices.jumps(jump, label, inner)
+            || self
+                .fields
+                .iter()
+                .any(|f| f.indices.jumps(jump, label, inner))
+    }
+}
+
+impl ControlFlow for PrimaryExpr {
+    fn jumps(&self, jump: Jump, label: Option<&str>, inner: bool) -> bool {
+        match self {
+            Self::Expr(exp) => exp.jumps(jump, label, inner),
+            Self::LVal(lval) => lval.jumps(jump, label, inner),
+            Self::Number(_) | Self::Bool(_) | Self::Str(_) => false,
+            Self::If(exp) => exp.jumps(jump, label, inner),
+        }
+    }
+}
+
+impl ControlFlow for IfExpr {
+    fn jumps(&self, jump: Jump, label: Option<&str>, inner: bool) -> bool {
+        self.cond.jumps(jump, label, inner)
+            || self.then.jumps(jump, label, inner)
+            || match &self.else_then {
+                ElseExpr::Block(block) => block.jumps(jump, label, inner),
+                ElseExpr::If(exp) => exp.jumps(jump, label, inner),
+            }
+    }
+}
+
+impl ControlFlow for ValueBlock {
+    fn jumps(&self, jump: Jump, label: Option<&str>, inner: bool) -> bool {
+        self.items.jumps(jump, label, inner) || self.value.jumps(jump, label, inner)
+    }
+}
+
+impl ControlFlow for UnaryExpr {
+    fn jumps(&self, jump: Jump, label: Option<&str>, inner: bool) -> bool {
+        match self {
+            Self::Primary(exp) => exp.jumps(jump, label, inner),
+            Self::Call(call) => call.args.jumps(jump, label, inner),
+            Self::Unary(_, exp) | Self::Cast(exp, _) => exp.jumps(jump, label, inner),
+            Self::AddrOf(lval) => lval.jumps(jump, label, inner),
+        }
+    }
+}
+
+/// Implements `ControlFlow` for binary expressions.
+macro_rules! impl_binary_flow {
+    ($($ty:ident: $operand:ident, $binary:ident;)*) => {
+        $(
+            impl ControlFlow for $ty {
+                fn jumps(&self, jump: Jump, label: Option<&str>, inner: bool) -> bool {
+                    match self {
+                        Self::$operand(exp) => exp.jumps(jump, label, inner),
+                        Self::$binary(lhs, .., rhs) => {
+                            lhs.jumps(jump, label, inner) || rhs.jumps(jump, label, inner)
+                        }
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_binary_flow! {
+    MulExpr: Unary, MulUnary;
+    AddExpr: Mul, AddMul;
+    ShiftExpr: Add, ShiftAdd;
+    RelExpr: Shift, RelShift;
+    EqExpr: Rel, EqRel;
+    BitAndExpr: Eq, BitAndEq;
+    BitXorExpr: BitAnd, BitXorBitAnd;
+    BitOrExpr: BitXor, BitOrBitXor;
+    LAndExpr: BitOr, LAndBitOr;
+    LOrExpr: LAnd, LOrLAnd;
+}
@@ -0,0 +1,128 @@
+//! Static checks of the AST, which run before the IR generation.
+//!
+//! The checks resolve names, give every expression a type and check the types
+//! of operators, calls, assignments, returns and indices. The IR generator
+//! reads the types and the constant values from the returned [`Types`],
+//! and assumes the AST is well-typed.
+
+mod check;
+pub mod error;
+mod eval;
+mod flow;
+mod scopes;
+mod ty;
+
+pub use ty::{Field, StructInfo, Ty};
+
+use crate::ast::CompUnit;
+use crate::error::FileId;
+use crate::session::Session;
+use check::Check;
+use scopes::Scopes;
+use std::any::{type_name, TypeId};
+use std::collections::HashMap;
+
+/// Types and constant values of the nodes of an AST, and layouts of the structs.
+///
+/// Expressions, operators, left values, variable definitions, parameters
+/// and return types are given types. The IR is only generated for ASTs
+/// without errors, where every node that is looked up has been checked,
+/// so looking up a node without an entry is a bug and panics.
+#[derive(Default)]
+pub struct Types<'ast> {
+    tys: HashMap<Node, Ty<'ast>>,
+    operands: HashMap<Node, Ty<'ast>>,
+    values: HashMap<Node, i32>,
+    structs: HashMap<&'ast str, StructInfo<'ast>>,
+}
+
+/// Identifies a node of the AST by its type and address,
+/// a node and its first field have the same address.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Node(TypeId, *const ());
+
+impl Node {
+    fn of<T: 'static>(node: &T) -> Self {
+        Self(TypeId::of::<T>(), node as *const T as *const ())
+    }
+}
+
+impl<'ast> Types<'ast> {
+    /// Returns the type of the given node.
+    pub fn ty<T: 'static>(&self, node: &T) -> &Ty<'ast> {
+        self.tys
+            .get(&Node::of(node))
+            .unwrap_or_else(|| missing::<T>("type"))
+    }
+
+    /// Returns the type the operands of the given comparison are converted to,
+    /// integer constants take the type of the other operand.
+    pub fn operand_ty<T: 'static>(&self, node: &T) -> &Ty<'ast> {
+        self.operands
+            .get(&Node::of(node))
+            .unwrap_or_else(|| missing::<T>("operand type"))
+    }
+
+    /// Returns the value of the given expression, which is the length of
+    /// an array dimension, or the initializer of a constant or a global value.
+    pub fn value<T: 'static>(&self, exp: &T) -> i32 {
+        self.values
+            .get(&Node::of(exp))
+            .copied()
+            .unwrap_or_else(|| missing::<T>("value"))
+    }
+
+    /// Returns the layout of the struct type by the given name,
+    /// `None` if the struct is not defined.
+    pub fn struct_info(&self, name: &str) -> Option<&StructInfo<'ast>> {
+        self.structs.get(name)
+    }
+
+    /// Returns the size and the alignment in bytes of
+    /// values of the given type, which is stored in memory.
+    fn layout(&self, ty: &Ty) -> (usize, usize) {
+        match ty {
+            Ty::Sized(ty) => (ty.size(), ty.size().min(4)),
+            Ty::Struct(name) => (self.struct_info(name).map_or(0, |s| s.size * 4), 4),
+            Ty::Array(elem, len) => {
+                let (size, align) = self.layout(elem);
+                (size * len.unwrap_or(0), align)
+            }
+            _ => (4, 4),
+        }
+    }
+
+    fn insert_ty<T: 'static>(&mut self, node: &T, ty: Ty<'ast>) {
+        self.tys.insert(Node::of(node), ty);
+    }
+
+    fn insert_operand_ty<T: 'static>(&mut self, node: &T, ty: Ty<'ast>) {
+        self.operands.insert(Node::of(node), ty);
+    }
+
+    fn insert_value<T: 'static>(&mut self, exp: &T, value: i32) {
+        self.values.insert(Node::of(exp), value);
+    }
+}
+
+/// Panics on the lookup of a node of type `T` without the given entry.
+fn missing<T>(entry: &str) -> ! {
+    panic!(
+        "no {} for the `{}` node, it was not checked",
+        entry,
+        type_name::<T>()
+    )
+}
+
+/// Checks the AST of the given source file.
+///
+/// Diagnostics are reported to the session. Returns the types of the expressions,
+/// which are complete only if there are no diagnostics.
+pub fn check<'ast>(comp_unit: &'ast CompUnit, session: &mut Session, file: FileId) -> Types<'ast> {
+    let mut scopes = Scopes::new(file);
+    if let Err(e) = comp_unit.check(&mut scopes) {
+        scopes.report(e);
+    }
+    session.report_all(scopes.take_diagnostics());
+    scopes.types
+}
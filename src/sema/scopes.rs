@@ -0,0 +1,157 @@
+use super::error::Error;
+use super::ty::{StructInfo, Ty};
+use super::Types;
+use crate::ast;
+use crate::error::{self, FileId};
+use std::collections::{HashMap, HashSet};
+
+pub struct Scopes<'ast> {
+    file: FileId,
+    vals: Vec<HashMap<&'ast str, Symbol<'ast>>>,
+    funs: HashMap<&'ast str, Signature<'ast>>,
+    /// Return type of the function being checked, `None` in global scope.
+    pub ret_ty: Option<Ty<'ast>>,
+    /// Labels of the enclosing loops, the innermost last.
+    pub loops: Vec<Option<&'ast ast::Label>>,
+    pub types: Types<'ast>,
+    /// Positions of the integer literals above `i32::MAX`.
+    large_literals: HashSet<(usize, usize)>,
+    diagnostics: Vec<error::Error>,
+}
+
+/// A named value.
+#[derive(Clone)]
+pub enum Symbol<'ast> {
+    /// Constant integer of the given type, `Int` or a fixed-width integer type.
+    Const(i32, Ty<'ast>),
+    /// Variable of the given type.
+    Var(Ty<'ast>),
+}
+
+/// Types of the parameters and the return value of a function.
+pub struct Signature<'ast> {
+    pub params: Vec<Ty<'ast>>,
+    pub ret: Ty<'ast>,
+}
+
+impl<'ast> Scopes<'ast> {
+    /// Creates a new `Scopes` for the given source file.
+    pub fn new(file: FileId) -> Self {
+        Self {
+            file,
+            vals: vec![HashMap::new()],
+            funs: HashMap::new(),
+            ret_ty: None,
+            loops: Vec::new(),
+            types: Types::default(),
+            large_literals: HashSet::new(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// Returns the source file the diagnostics are reported for.
+    pub fn file(&self) -> FileId {
+        self.file
+    }
+
+    /// Reports a diagnostic, the checks continue.
+    pub fn report(&mut self, error: error::Error) {
+        self.diagnostics.push(error);
+    }
+
+    /// Takes all diagnostics reported so far.
+    pub fn take_diagnostics(&mut self) -> Vec<error::Error> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    /// Returns the innermost loop, or the loop with the given label.
+    /// Returns `None` if there is no such loop.
+    pub fn loop_label(&self, label: Option<&str>) -> Option<Option<&'ast ast::Label>> {
+        match label {
+            Some(name) => self
+                .loops
+                .iter()
+                .rev()
+                .find(|l| l.is_some_and(|l| l.name == name))
+                .copied(),
+            None => self.loops.last().copied(),
+        }
+    }
+
+    /// Returns `true` if is currently in global scope.
+    pub fn is_global(&self) -> bool {
+        self.ret_ty.is_none()
+    }
+
+    /// Inserts a new value to the current scope.
+    pub fn new_value(&mut self, id: &'ast str, value: Symbol<'ast>) -> Result<(), Error> {
+        let is_global = self.is_global();
+        let cur = self.vals.last_mut().unwrap();
+        if cur.contains_key(id) || (is_global && self.funs.contains_key(id)) {
+            Err(Error::DuplicatedDef)
+        } else {
+            cur.insert(id, value);
+            Ok(())
+        }
+    }
+
+    /// Returns the value by the given identifier.
+    pub fn value(&self, id: &str) -> Result<&Symbol<'ast>, Error> {
+        self.vals
+            .iter()
+            .rev()
+            .find_map(|vals| vals.get(id))
+            .ok_or(Error::SymbolNotFound)
+    }
+
+    /// Inserts a new funtion, function names clash with global values.
+    pub fn new_fun(&mut self, id: &'ast str, sig: Signature<'ast>) -> Result<(), Error> {
+        if self.funs.contains_key(id) || self.vals.first().unwrap().contains_key(id) {
+            Err(Error::DuplicatedDef)
+        } else {
+            self.funs.insert(id, sig);
+            Ok(())
+        }
+    }
+
+    /// Returns the signature of the funtion by the given identifier.
+    pub fn fun(&self, id: &str) -> Result<&Signature<'ast>, Error> {
+        self.funs.get(id).ok_or(Error::SymbolNotFound)
+    }
+
+    /// Inserts a new struct type, struct names do not clash with values.
+    pub fn new_struct(&mut self, id: &'ast str, info: StructInfo<'ast>) -> Result<(), Error> {
+        if self.types.structs.contains_key(id) {
+            Err(Error::DuplicatedDef)
+        } else {
+            self.types.structs.insert(id, info);
+            Ok(())
+        }
+    }
+
+    /// Returns the struct type by the given identifier.
+    pub fn struct_info(&self, id: &str) -> Result<&StructInfo<'ast>, Error> {
+        self.types.struct_info(id).ok_or(Error::SymbolNotFound)
+    }
+
+    /// Records an integer literal above `i32::MAX`.
+    pub fn new_large_literal(&mut self, num: &ast::Number) {
+        self.large_literals.insert((num.pos.0, num.pos.1));
+    }
+
+    /// Returns `true` if the expression is an integer literal above `i32::MAX`.
+    pub fn is_large_literal(&self, exp: &impl ast::Span) -> bool {
+        let pos = exp.span();
+        self.large_literals.contains(&(pos.0, pos.1))
+    }
+
+    /// Enters a new scope.
+    pub fn enter(&mut self) {
+        self.vals.push(HashMap::new());
+    }
+
+    /// Exits from the current scope.
+    pub fn exit(&mut self) {
+        self.vals.pop();
+    }
+}
@@ -0,0 +1,100 @@
+use crate::ast::IntTy;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Type of a value or an expression.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Ty<'ast> {
+    /// Type of an expression that failed to check, which is compatible
+    /// with every type, so that an error is only reported once.
+    Error,
+    Unit,
+    Int,
+    Bool,
+    Sized(IntTy),
+    Struct(&'ast str),
+    Pointer(Box<Ty<'ast>>),
+    /// Array of the given element type and length,
+    /// the length of multi-dimensional array parameters is unknown.
+    Array(Box<Ty<'ast>>, Option<usize>),
+}
+
+impl<'ast> Ty<'ast> {
+    /// Returns the array type with the given dimension lengths,
+    /// or the element type if there are no dimensions.
+    pub fn array(elem: Self, lens: &[usize]) -> Self {
+        lens.iter()
+            .rev()
+            .fold(elem, |ty, &len| Self::Array(Box::new(ty), Some(len)))
+    }
+
+    /// Returns the element type and the number of dimensions of an array,
+    /// other types have no dimensions.
+    pub fn elem(&self) -> (&Self, usize) {
+        match self {
+            Self::Array(elem, _) => {
+                let (elem, dims) = elem.elem();
+                (elem, dims + 1)
+            }
+            ty => (ty, 0),
+        }
+    }
+
+    /// Returns `true` if the type is `Int` or a fixed-width integer type.
+    pub fn is_int(&self) -> bool {
+        matches!(self, Self::Error | Self::Int | Self::Sized(_))
+    }
+
+    /// Returns `true` if a value of the given type can be used where a value
+    /// of this type is expected, arrays decay to pointers to their first element.
+    pub fn accepts(&self, ty: &Self) -> bool {
+        match (self, ty) {
+            (Self::Error, _) | (_, Self::Error) => true,
+            (Self::Pointer(base), Self::Array(elem, _)) => base == elem,
+            (Self::Array(base, None), Self::Array(elem, _)) => base == elem,
+            _ => self == ty,
+        }
+    }
+}
+
+/// Layout of a struct type, the fields are laid out in order and aligned
+/// to their size, Ints, pointers, structs and `I64` values are aligned to words.
+pub struct StructInfo<'ast> {
+    /// Size in words, the last field is padded to a whole word.
+    pub size: usize,
+    pub fields: HashMap<&'ast str, Field<'ast>>,
+}
+
+/// Type and position of a struct field.
+pub struct Field<'ast> {
+    /// Type of the field, array fields have array types.
+    pub ty: Ty<'ast>,
+    /// Offset in bytes from the start of the struct.
+    pub offset: usize,
+}
+
+impl fmt::Display for Ty<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "{{error}}"),
+            Self::Unit => write!(f, "Unit"),
+            Self::Int => write!(f, "Int"),
+            Self::Bool => write!(f, "Bool"),
+            Self::Sized(ty) => write!(f, "{}", ty),
+            Self::Struct(name) => write!(f, "{}", name),
+            Self::Pointer(ty) => write!(f, "*{}", ty),
+            Self::Array(..) => {
+                let mut ty = self;
+                let mut dims = String::new();
+                while let Self::Array(elem, len) = ty {
+                    match len {
+                        Some(len) => dims += &format!("[{}]", len),
+                        None => dims += "[]",
+                    }
+                    ty = elem;
+                }
+                write!(f, "{}{}", ty, dims)
+            }
+        }
+    }
+}
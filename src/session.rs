@@ -0,0 +1,80 @@
+use crate::error::{Error, FileId};
+use codespan_reporting::diagnostic::Diagnostic;
+use codespan_reporting::files::{Files, SimpleFiles};
+use codespan_reporting::term;
+use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
+
+/// State of a single compiler invocation.
+///
+/// Owns the source files being compiled and the diagnostics reported
+/// for them, so that several sessions can live in the same process.
+pub struct Session {
+    files: SimpleFiles<String, String>,
+    color: ColorChoice,
+    diagnostics: Vec<Error>,
+}
+
+impl Session {
+    /// Creates a new session which emits diagnostics with the given color choice.
+    pub fn new(color: ColorChoice) -> Self {
+        Self {
+            files: SimpleFiles::new(),
+            color,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// Adds a source file to the session, returns its identifier.
+    pub fn add_file(&mut self, name: String, source: String) -> FileId {
+        self.files.add(name, source)
+    }
+
+    /// Returns the name of the given file.
+    pub fn name(&self, file: FileId) -> String {
+        self.files.name(file).unwrap()
+    }
+
+    /// Returns the source code of the given file.
+    pub fn source(&self, file: FileId) -> &str {
+        self.files.get(file).unwrap().source()
+    }
+
+    /// Reports a diagnostic, it is kept for the lifetime of the session.
+    pub fn report(&mut self, error: Error) {
+        self.diagnostics.push(error);
+    }
+
+    /// Reports all the given diagnostics.
+    pub fn report_all(&mut self, errors: impl IntoIterator<Item = Error>) {
+        self.diagnostics.extend(errors);
+    }
+
+    /// Returns the number of diagnostics reported so far.
+    pub fn error_count(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    /// Returns the diagnostics reported so far.
+    pub fn diagnostics(&self) -> &[Error] {
+        &self.diagnostics
+    }
+
+    /// Returns `true` if any diagnostic has been reported.
+    pub fn has_errors(&self) -> bool {
+        !self.diagnostics.is_empty()
+    }
+
+    /// Writes all diagnostics reported so far to stderr.
+    pub fn emit(&self) {
+        let writer = StandardStream::stderr(self.color);
+        let config = term::Config::default();
+        for error in &self.diagnostics {
+            let diagnostic = Diagnostic::error()
+                .with_message(&error.message)
+                .with_code(&error.code)
+                .with_labels(error.labels.clone())
+                .with_notes(error.note.clone());
+            term::emit(&mut writer.lock(), &config, &self.files, &diagnostic).unwrap();
+        }
+    }
+}
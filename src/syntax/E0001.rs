@@ -1,13 +1,12 @@
 use crate::ast;
-use crate::error::{Error, Reporter};
+use crate::error::{Error, FileId};
 use crate::syntax::Checker;
 use codespan_reporting::diagnostic::Label;
 
-use super::REPORTER;
-
 /// E0001: The entry module should start with the main function.
 pub struct E0001<'ast> {
     ast: &'ast ast::CompUnit,
+    file: FileId,
 }
 
 impl<'ast> Checker<'ast> for E0001<'ast> {
@@ -20,7 +19,7 @@ impl<'ast> Checker<'ast> for E0001<'ast> {
                             message: String::from("Incorrect main function"),
                             code: String::from("E0001"),
                             labels: vec![Label::primary(
-                                unsafe { Reporter::reporter_id(REPORTER) },
+                                self.file,
                                 fun_def.id.pos.0..fun_def.id.pos.1,
                             )
                             .with_message(format!("expected `main`, found {}", fun_def.id.name))],
@@ -39,7 +38,7 @@ impl<'ast> Checker<'ast> for E0001<'ast> {
 }
 
 impl E0001<'_> {
-    pub fn new(ast: &ast::CompUnit) -> Box<E0001> {
-        Box::new(E0001 { ast })
+    pub fn new(ast: &ast::CompUnit, file: FileId) -> Box<E0001<'_>> {
+        Box::new(E0001 { ast, file })
     }
 }
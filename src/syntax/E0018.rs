@@ -0,0 +1,24 @@
+use crate::error::{Error, FileId};
+use codespan_reporting::diagnostic::Label;
+
+use super::expected_note;
+
+/// E0018: Unexpected token
+pub struct E0018<'a> {
+    pub token: (usize, &'a str, usize),
+    pub expected: &'a [String],
+}
+
+impl E0018<'_> {
+    pub fn run(&self, file: FileId) -> Error {
+        let (start, token, end) = self.token;
+        Error {
+            message: String::from("Unexpected token"),
+            code: String::from("E0018"),
+            labels: vec![
+                Label::primary(file, start..end).with_message(format!("unexpected `{}`", token))
+            ],
+            note: expected_note(self.expected).into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use crate::error::{Error, FileId};
+use codespan_reporting::diagnostic::Label;
+
+use super::expected_note;
+
+/// E0019: Unexpected end of file
+pub struct E0019<'a> {
+    pub location: usize,
+    pub expected: &'a [String],
+}
+
+impl E0019<'_> {
+    pub fn run(&self, file: FileId) -> Error {
+        Error {
+            message: String::from("Unexpected end of file"),
+            code: String::from("E0019"),
+            labels: vec![Label::primary(file, self.location..self.location)
+                .with_message("the source ends here")],
+            note: expected_note(self.expected).into_iter().collect(),
+        }
+    }
+}
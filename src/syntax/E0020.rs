@@ -0,0 +1,24 @@
+use crate::error::{Error, FileId};
+use codespan_reporting::diagnostic::Label;
+
+/// E0020: Invalid token
+pub struct E0020<'a> {
+    pub location: usize,
+    pub source: &'a str,
+}
+
+impl E0020<'_> {
+    pub fn run(&self, file: FileId) -> Error {
+        let c = self.source[self.location..].chars().next();
+        let end = self.location + c.map_or(0, char::len_utf8);
+        Error {
+            message: String::from("Invalid token"),
+            code: String::from("E0020"),
+            labels: vec![Label::primary(file, self.location..end)
+                .with_message("this character can not start any token")],
+            note: vec![String::from(
+                "Only ASCII identifiers, literals, operators and `#` comments are allowed",
+            )],
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use crate::error::{Error, FileId};
+use codespan_reporting::diagnostic::Label;
+
+/// E0021: Extra token after the end of the program
+pub struct E0021<'a> {
+    pub token: (usize, &'a str, usize),
+}
+
+impl E0021<'_> {
+    pub fn run(&self, file: FileId) -> Error {
+        let (start, token, end) = self.token;
+        Error {
+            message: String::from("Extra token"),
+            code: String::from("E0021"),
+            labels: vec![
+                Label::primary(file, start..end).with_message(format!("unexpected `{}`", token))
+            ],
+            note: vec![String::from(
+                "Only declarations and function definitions can appear at the top level",
+            )],
+        }
+    }
+}
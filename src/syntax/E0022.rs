@@ -0,0 +1,39 @@
+use crate::ast::Position;
+use crate::error::{Error, FileId};
+use crate::syntax::LiteralError;
+use codespan_reporting::diagnostic::Label;
+
+/// E0022: Invalid literal, reported by the grammar actions
+pub struct E0022<'a> {
+    pub pos: &'a Position,
+    pub error: &'a LiteralError,
+}
+
+impl E0022<'_> {
+    pub fn run(&self, file: FileId) -> Error {
+        let (label, note) = match self.error {
+            LiteralError::TooLarge => (
+                "integer literal is too large",
+                "Integer literals are at most `2147483647`, or `4294967295` where `U32` values are expected",
+            ),
+            LiteralError::UnknownEscape => (
+                "unknown escape sequence",
+                r#"The escape sequences are `\n`, `\t`, `\r`, `\0`, `\\`, `\'`, `\"` and `\xHH`"#,
+            ),
+            LiteralError::InvalidHexEscape => (
+                "invalid hexadecimal escape",
+                "Hexadecimal escapes have exactly two hexadecimal digits, such as `\\x41`",
+            ),
+            LiteralError::MultiByteChar => (
+                "character literal must be a single byte",
+                "Character literals hold a single ASCII character or escape sequence",
+            ),
+        };
+        Error {
+            message: String::from("Invalid literal"),
+            code: String::from("E0022"),
+            labels: vec![Label::primary(file, self.pos.0..self.pos.1).with_message(label)],
+            note: vec![String::from(note)],
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use crate::ast::Position;
+use crate::error::{Error, FileId};
+use codespan_reporting::diagnostic::Label;
+
+/// E0039: Assignment used as a condition, reported by the grammar actions
+pub struct E0039<'a> {
+    pub pos: &'a Position,
+}
+
+impl E0039<'_> {
+    pub fn run(&self, file: FileId) -> Error {
+        Error {
+            message: String::from("Assignment used as a condition"),
+            code: String::from("E0039"),
+            labels: vec![Label::primary(file, self.pos.0..self.pos.1)
+                .with_message("assignments are statements, not expressions")],
+            note: vec![String::from(
+                "Use `==` to compare values, or assign before the condition",
+            )],
+        }
+    }
+}
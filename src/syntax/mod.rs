@@ -1,10 +1,169 @@
 use crate::ast;
-use crate::error::{Error, Reporter};
-
-pub static mut REPORTER: Option<Reporter> = None;
+use crate::error::{Error, FileId};
+use crate::ophelia::CompUnitParser;
+use crate::session::Session;
+use lalrpop_util::lexer::Token;
 
 #[allow(non_snake_case)]
 mod E0001;
+#[allow(non_snake_case)]
+mod E0018;
+#[allow(non_snake_case)]
+mod E0019;
+#[allow(non_snake_case)]
+mod E0020;
+#[allow(non_snake_case)]
+mod E0021;
+#[allow(non_snake_case)]
+mod E0022;
+#[allow(non_snake_case)]
+mod E0039;
+
+type ParseError<'input> =
+    lalrpop_util::ParseError<usize, Token<'input>, (ast::Position, GrammarError)>;
+
+/// Errors reported by the grammar actions.
+#[derive(Debug)]
+pub enum GrammarError {
+    /// An invalid literal, with the reason.
+    InvalidLiteral(LiteralError),
+    /// An assignment used as a condition.
+    AssignInCond,
+}
+
+/// Reasons of invalid literals.
+#[derive(Debug)]
+pub enum LiteralError {
+    TooLarge,
+    UnknownEscape,
+    InvalidHexEscape,
+    MultiByteChar,
+}
+
+/// Parses the digits of an integer literal in the given radix. Literals up to
+/// `u32::MAX` are parsed, so that every `U32` value can be written, and are
+/// stored by their bit patterns. The static checks only accept literals above
+/// `i32::MAX` where `U32` values are expected.
+pub fn parse_int(digits: &str, radix: u32) -> Result<i32, LiteralError> {
+    u32::from_str_radix(digits, radix)
+        .map(|value| value as i32)
+        .map_err(|_| LiteralError::TooLarge)
+}
+
+/// Decodes the escape sequences in the body of a character or string literal.
+pub fn unescape(literal: &str) -> Result<Vec<u8>, LiteralError> {
+    let mut bytes = Vec::new();
+    let mut chars = literal.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        bytes.push(match chars.next() {
+            Some('n') => b'\n',
+            Some('t') => b'\t',
+            Some('r') => b'\r',
+            Some('0') => 0,
+            Some('\\') => b'\\',
+            Some('\'') => b'\'',
+            Some('"') => b'"',
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                if hex.len() != 2 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(LiteralError::InvalidHexEscape);
+                }
+                u8::from_str_radix(&hex, 16).unwrap()
+            }
+            _ => return Err(LiteralError::UnknownEscape),
+        });
+    }
+    Ok(bytes)
+}
+
+/// Parses the given source file of the session into an AST.
+///
+/// Syntax errors are reported to the session. The parser recovers from errors
+/// in statements, blocks and global items, in which case a partial AST containing
+/// error nodes is returned. Returns `None` if the parser could not recover.
+pub fn parse(session: &mut Session, file: FileId) -> Option<ast::CompUnit> {
+    let source = session.source(file);
+    let mut recovered = Vec::new();
+    let result = CompUnitParser::new().parse(&mut recovered, source);
+    let mut errors: Vec<_> = recovered
+        .into_iter()
+        .map(|recovery| parse_error(recovery.error, source, file))
+        .collect();
+    let ast = result
+        .map_err(|e| errors.push(parse_error(e, source, file)))
+        .ok();
+    session.report_all(errors);
+    ast
+}
+
+/// Converts the given parse error into a diagnostic.
+fn parse_error(error: ParseError, source: &str, file: FileId) -> Error {
+    match error {
+        ParseError::UnrecognizedToken {
+            token: (start, token, end),
+            expected,
+        } => E0018::E0018 {
+            token: (start, token.1, end),
+            expected: &expected,
+        }
+        .run(file),
+        ParseError::UnrecognizedEof { location, expected } => E0019::E0019 {
+            location,
+            expected: &expected,
+        }
+        .run(file),
+        ParseError::InvalidToken { location } => E0020::E0020 { location, source }.run(file),
+        ParseError::ExtraToken {
+            token: (start, token, end),
+        } => E0021::E0021 {
+            token: (start, token.1, end),
+        }
+        .run(file),
+        ParseError::User {
+            error: (pos, GrammarError::InvalidLiteral(error)),
+        } => E0022::E0022 {
+            pos: &pos,
+            error: &error,
+        }
+        .run(file),
+        ParseError::User {
+            error: (pos, GrammarError::AssignInCond),
+        } => E0039::E0039 { pos: &pos }.run(file),
+    }
+}
+
+/// Returns a note listing the expected tokens, if any.
+fn expected_note(expected: &[String]) -> Option<String> {
+    let mut tokens: Vec<_> = expected
+        .iter()
+        .map(|token| match token.strip_prefix("r#\"") {
+            Some(regex) => String::from(regex_name(regex.trim_end_matches("\"#"))),
+            None => format!("`{}`", token.trim_matches('"')),
+        })
+        .collect();
+    tokens.dedup();
+    match tokens.as_slice() {
+        [] => None,
+        [token] => Some(format!("expected {}", token)),
+        _ => Some(format!("expected one of {}", tokens.join(", "))),
+    }
+}
+
+/// Returns the name of the tokens matched by the given regex terminal.
+fn regex_name(regex: &str) -> &'static str {
+    match regex {
+        "[_a-zA-Z][_a-zA-Z0-9]*" => "identifier",
+        "'[_a-zA-Z][_a-zA-Z0-9]*" => "label",
+        _ if regex.starts_with("\\\"") => "string literal",
+        _ if regex.starts_with('\'') => "character literal",
+        _ => "integer literal",
+    }
+}
 
 pub(crate) trait Checker<'ast> {
     fn run(&self) -> Result<(), Error>;
@@ -15,24 +174,18 @@ pub struct CheckerManager<'ast> {
 }
 
 impl<'ast> CheckerManager<'ast> {
-    pub fn new(ast: &'ast ast::CompUnit) -> Self {
-        unsafe {
-            CheckerManager {
-                passes: vec![E0001::E0001::new(&ast)],
-            }
+    pub fn new(ast: &'ast ast::CompUnit, file: FileId) -> Self {
+        CheckerManager {
+            passes: vec![E0001::E0001::new(ast, file)],
         }
     }
 
-    pub fn run(&self) -> Vec<Error> {
-        let mut pass_results = Vec::new();
-
+    /// Runs all checkers, reports their errors to the session.
+    pub fn run(&self, session: &mut Session) {
         for pass in &self.passes {
-            match pass.run() {
-                Err(e) => pass_results.push(e),
-                _ => (),
+            if let Err(e) = pass.run() {
+                session.report(e)
             }
         }
-
-        pass_results
     }
 }
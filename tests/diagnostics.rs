@@ -0,0 +1,83 @@
+//! Diagnostics tests: compiles the erroneous programs in `tests/errors` and
+//! compares the reported errors with the expectation file.
+//!
+//! Each `NAME.oph` comes with `NAME.err`, holding the expected errors in the
+//! order they are reported, one per line. A line is an error code, optionally
+//! followed by the `line:column` where the primary label of the error starts.
+
+use codespan_reporting::diagnostic::LabelStyle;
+use ophelia::error::Error;
+use ophelia::Options;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn errors() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/errors");
+    let mut cases: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "oph"))
+        .collect();
+    cases.sort();
+    let mut failures = Vec::new();
+    for path in cases {
+        let source = fs::read_to_string(&path).unwrap();
+        let expected = fs::read_to_string(path.with_extension("err")).unwrap();
+        let errors = match ophelia::compile(&source, Options::default()) {
+            Ok(_) => String::new(),
+            Err(diagnostics) => {
+                // positions are only compared if the expectation has them
+                let mut lines = expected.lines();
+                diagnostics
+                    .errors()
+                    .iter()
+                    .map(|e| match lines.next() {
+                        Some(line) if line.contains(' ') => {
+                            format!("{} {}\n", e.code, position(&source, e))
+                        }
+                        _ => format!("{}\n", e.code),
+                    })
+                    .collect()
+            }
+        };
+        if errors != expected {
+            failures.push(format!(
+                "{}: expected\n{}found\n{}",
+                path.display(),
+                expected,
+                errors
+            ));
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn expected_tokens() {
+    let note = |source: &str| {
+        let diagnostics = ophelia::compile(source, Options::default()).err().unwrap();
+        diagnostics.errors()[0].note.join("\n")
+    };
+    assert!(note("let main() => Int { putint(; }")
+        .ends_with("string literal, character literal, integer literal, identifier"));
+    assert_eq!(
+        note("let main() => Int { while (true) { break x; } }"),
+        "expected one of `;`, label"
+    );
+}
+
+/// Returns the `line:column` where the primary label of the error starts.
+fn position(source: &str, error: &Error) -> String {
+    let Some(label) = error
+        .labels
+        .iter()
+        .find(|label| label.style == LabelStyle::Primary)
+    else {
+        return String::from("?");
+    };
+    let before = &source[..label.range.start];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    format!("{}:{}", line, column)
+}
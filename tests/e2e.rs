@@ -0,0 +1,97 @@
+//! End-to-end tests: compiles the sample programs in `tests/cases`, runs the
+//! generated assembly in the emulator and the generated Koopa IR in the
+//! interpreter, and compares the results with the expectation file.
+//!
+//! Each `NAME.oph` comes with `NAME.out`, holding the expected stdout followed
+//! by the exit code on its own line, and optionally `NAME.in` as stdin.
+
+mod emulator;
+
+use ophelia::ir::interp;
+use ophelia::{Emit, Options, Output};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Returns the paths of all sample programs.
+fn cases() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/cases");
+    let mut cases: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "oph"))
+        .collect();
+    cases.sort();
+    cases
+}
+
+/// Formats stdout and exit code the way expectation files are written.
+fn expected_output(stdout: &[u8], code: i32) -> String {
+    let mut output = String::from_utf8_lossy(stdout).into_owned();
+    if !output.is_empty() && !output.ends_with('\n') {
+        output.push('\n');
+    }
+    output + &format!("{}\n", code)
+}
+
+/// Compiles the given sample program.
+fn compile(path: &Path, emit: Emit) -> Result<Output, String> {
+    let source = fs::read_to_string(path).unwrap();
+    let options = Options {
+        emit,
+        file_name: path.display().to_string(),
+        ..Options::default()
+    };
+    ophelia::compile(&source, options).map_err(|diagnostics| {
+        diagnostics.emit();
+        String::from("failed to compile")
+    })
+}
+
+/// Runs the generated assembly in the emulator, returns the formatted output.
+fn run_riscv(path: &Path, input: &[u8]) -> Result<String, String> {
+    let Output::Riscv(asm) = compile(path, Emit::Riscv)? else {
+        unreachable!()
+    };
+    let exit = emulator::run(&asm, input)?;
+    Ok(expected_output(&exit.stdout, exit.code))
+}
+
+/// Runs the generated Koopa IR in the interpreter, returns the formatted output.
+fn run_koopa(path: &Path, mut input: &[u8]) -> Result<String, String> {
+    let Output::Koopa(program) = compile(path, Emit::Koopa)? else {
+        unreachable!()
+    };
+    let mut stdout = Vec::new();
+    let code = interp::run(&program, &mut input, &mut stdout).map_err(|e| e.to_string())?;
+    Ok(expected_output(&stdout, code & 0xff))
+}
+
+/// Runs all sample programs, panics with all mismatches.
+fn check(run: fn(&Path, &[u8]) -> Result<String, String>) {
+    let mut failures = Vec::new();
+    for path in cases() {
+        let expected = fs::read_to_string(path.with_extension("out")).unwrap();
+        let input = fs::read(path.with_extension("in")).unwrap_or_default();
+        match run(&path, &input) {
+            Ok(output) if output == expected => {}
+            Ok(output) => failures.push(format!(
+                "{}: expected\n{}found\n{}",
+                path.display(),
+                expected,
+                output
+            )),
+            Err(e) => failures.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn riscv() {
+    check(run_riscv)
+}
+
+#[test]
+fn koopa() {
+    check(run_koopa)
+}
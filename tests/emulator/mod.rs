@@ -0,0 +1,477 @@
+//! A small emulator of the RV32IM assembly emitted by the compiler,
+//! with the SysY runtime library built in.
+//!
+//! Instructions are executed from the assembly text directly: each
+//! instruction occupies 4 bytes of the text address space, so return
+//! addresses can be stored to the stack like any other value.
+
+use std::collections::HashMap;
+
+/// Size of the memory, the stack grows down from the end of it.
+const MEM_SIZE: u32 = 1 << 24;
+/// Address of the first byte of the data section.
+const DATA_BASE: u32 = 0x1000;
+/// Address of the first instruction.
+const TEXT_BASE: u32 = 0x4000_0000;
+/// Maximum number of executed instructions before giving up.
+const MAX_STEPS: u64 = 200_000_000;
+
+/// Result of a finished program.
+pub struct Exit {
+    pub stdout: Vec<u8>,
+    pub code: i32,
+}
+
+/// Runs the `main` function of the given assembly with the given stdin.
+pub fn run(asm: &str, input: &[u8]) -> Result<Exit, String> {
+    let program = Program::parse(asm)?;
+    let mut machine = Machine::new(&program, input);
+    machine.run()
+}
+
+#[derive(Debug)]
+enum Inst {
+    Li(usize, i32),
+    La(usize, String),
+    Mv(usize, usize),
+    Op(String, usize, usize, usize),
+    OpImm(String, usize, usize, i32),
+    Unary(String, usize, usize),
+    Load(String, usize, i32, usize),
+    Store(String, usize, i32, usize),
+    Branch(String, usize, usize, String),
+    J(String),
+    Jr(usize),
+    Call(String),
+    Ret,
+}
+
+struct Program {
+    insts: Vec<Inst>,
+    data: Vec<u8>,
+    symbols: HashMap<String, u32>,
+    /// `.word` directives referring to symbols, by their offset in the data.
+    relocs: Vec<(usize, String)>,
+}
+
+impl Program {
+    fn parse(asm: &str) -> Result<Self, String> {
+        let mut program = Program {
+            insts: Vec::new(),
+            data: Vec::new(),
+            symbols: HashMap::new(),
+            relocs: Vec::new(),
+        };
+        let mut in_text = true;
+        for (num, line) in asm.lines().enumerate() {
+            program
+                .parse_line(line, &mut in_text)
+                .map_err(|e| format!("line {}: {}: `{}`", num + 1, e, line.trim()))?;
+        }
+        for (offset, symbol) in &program.relocs {
+            let addr = program
+                .symbols
+                .get(symbol)
+                .ok_or_else(|| format!("undefined symbol `{}`", symbol))?;
+            program.data[*offset..*offset + 4].copy_from_slice(&addr.to_le_bytes());
+        }
+        Ok(program)
+    }
+
+    fn parse_line(&mut self, line: &str, in_text: &mut bool) -> Result<(), String> {
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            return Ok(());
+        }
+        if let Some(label) = line.strip_suffix(':') {
+            let addr = if *in_text {
+                TEXT_BASE + 4 * self.insts.len() as u32
+            } else {
+                DATA_BASE + self.data.len() as u32
+            };
+            self.symbols.insert(label.to_string(), addr);
+            return Ok(());
+        }
+        let (op, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let args: Vec<_> = rest
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect();
+        match op {
+            ".text" => *in_text = true,
+            ".data" => *in_text = false,
+            ".globl" => {}
+            ".word" => match imm(args[0]) {
+                Ok(value) => self.push_data(value.to_le_bytes().to_vec()),
+                Err(_) => {
+                    self.relocs.push((self.data.len(), args[0].to_string()));
+                    self.push_data(vec![0; 4]);
+                }
+            },
+            ".half" => self.push_data((imm(args[0])? as i16).to_le_bytes().to_vec()),
+            ".byte" => self.push_data(vec![imm(args[0])? as u8]),
+            ".zero" => self.push_data(vec![0; imm(args[0])? as usize]),
+            ".align" | ".p2align" => {
+                let align = 1 << imm(args[0])?;
+                while !self.data.len().is_multiple_of(align) {
+                    self.data.push(0);
+                }
+            }
+            _ => {
+                let inst = parse_inst(op, &args)?;
+                self.insts.push(inst);
+            }
+        }
+        Ok(())
+    }
+
+    fn push_data(&mut self, bytes: Vec<u8>) {
+        self.data.extend(bytes);
+    }
+}
+
+fn parse_inst(op: &str, args: &[&str]) -> Result<Inst, String> {
+    let arity = |n: usize| {
+        if args.len() == n {
+            Ok(())
+        } else {
+            Err(format!("expected {} operands", n))
+        }
+    };
+    let inst = match op {
+        "li" => {
+            arity(2)?;
+            Inst::Li(reg(args[0])?, imm(args[1])?)
+        }
+        "la" => {
+            arity(2)?;
+            Inst::La(reg(args[0])?, args[1].to_string())
+        }
+        "mv" => {
+            arity(2)?;
+            Inst::Mv(reg(args[0])?, reg(args[1])?)
+        }
+        "add" | "sub" | "mul" | "mulhu" | "div" | "divu" | "rem" | "remu" | "and" | "or"
+        | "xor" | "sll" | "srl" | "sra" | "slt" | "sltu" | "sgt" | "sgtu" => {
+            arity(3)?;
+            Inst::Op(op.to_string(), reg(args[0])?, reg(args[1])?, reg(args[2])?)
+        }
+        "addi" | "andi" | "ori" | "xori" | "slli" | "srli" | "srai" | "slti" | "sltiu" => {
+            arity(3)?;
+            let op = op.strip_suffix('i').unwrap().to_string();
+            Inst::OpImm(op, reg(args[0])?, reg(args[1])?, imm(args[2])?)
+        }
+        "seqz" | "snez" | "neg" | "not" => {
+            arity(2)?;
+            Inst::Unary(op.to_string(), reg(args[0])?, reg(args[1])?)
+        }
+        "lw" | "lh" | "lhu" | "lb" | "lbu" => {
+            arity(2)?;
+            let (offset, base) = mem(args[1])?;
+            Inst::Load(op.to_string(), reg(args[0])?, offset, base)
+        }
+        "sw" | "sh" | "sb" => {
+            arity(2)?;
+            let (offset, base) = mem(args[1])?;
+            Inst::Store(op.to_string(), reg(args[0])?, offset, base)
+        }
+        "bnez" | "beqz" => {
+            arity(2)?;
+            Inst::Branch(op.to_string(), reg(args[0])?, 0, args[1].to_string())
+        }
+        "beq" | "bne" | "blt" | "bge" | "bltu" | "bgeu" | "bgt" | "ble" => {
+            arity(3)?;
+            Inst::Branch(
+                op.to_string(),
+                reg(args[0])?,
+                reg(args[1])?,
+                args[2].to_string(),
+            )
+        }
+        "j" => {
+            arity(1)?;
+            Inst::J(args[0].to_string())
+        }
+        "jr" => {
+            arity(1)?;
+            Inst::Jr(reg(args[0])?)
+        }
+        "call" => {
+            arity(1)?;
+            Inst::Call(args[0].to_string())
+        }
+        "ret" => {
+            arity(0)?;
+            Inst::Ret
+        }
+        _ => return Err(format!("unknown instruction `{}`", op)),
+    };
+    Ok(inst)
+}
+
+fn imm(s: &str) -> Result<i32, String> {
+    let parsed = match s.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).map(|v| v as i32),
+        None => s.parse::<i64>().map(|v| v as i32),
+    };
+    parsed.map_err(|_| format!("invalid immediate `{}`", s))
+}
+
+fn reg(s: &str) -> Result<usize, String> {
+    const ABI: [&str; 32] = [
+        "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4",
+        "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+        "t5", "t6",
+    ];
+    if let Some(i) = ABI.iter().position(|&r| r == s) {
+        return Ok(i);
+    }
+    match s {
+        "fp" => Ok(8),
+        _ => s
+            .strip_prefix('x')
+            .and_then(|n| n.parse().ok())
+            .filter(|&n: &usize| n < 32)
+            .ok_or_else(|| format!("invalid register `{}`", s)),
+    }
+}
+
+/// Parses a memory operand like `4(sp)`.
+fn mem(s: &str) -> Result<(i32, usize), String> {
+    let (offset, base) = s
+        .strip_suffix(')')
+        .and_then(|s| s.split_once('('))
+        .ok_or_else(|| format!("invalid memory operand `{}`", s))?;
+    Ok((imm(offset)?, reg(base)?))
+}
+
+struct Machine<'p> {
+    program: &'p Program,
+    regs: [i32; 32],
+    mem: Vec<u8>,
+    pc: u32,
+    input: &'p [u8],
+    stdout: Vec<u8>,
+}
+
+impl<'p> Machine<'p> {
+    fn new(program: &'p Program, input: &'p [u8]) -> Self {
+        let mut mem = vec![0; MEM_SIZE as usize];
+        let data = DATA_BASE as usize..DATA_BASE as usize + program.data.len();
+        mem[data].copy_from_slice(&program.data);
+        let mut regs = [0; 32];
+        regs[2] = MEM_SIZE as i32;
+        Self {
+            program,
+            regs,
+            mem,
+            pc: 0,
+            input,
+            stdout: Vec::new(),
+        }
+    }
+
+    fn run(&mut self) -> Result<Exit, String> {
+        // returning from `main` jumps to address 0, which stops the machine
+        self.regs[1] = 0;
+        self.pc = self.symbol("main")?;
+        for _ in 0..MAX_STEPS {
+            if self.pc == 0 {
+                return Ok(Exit {
+                    stdout: std::mem::take(&mut self.stdout),
+                    code: self.regs[10] & 0xff,
+                });
+            }
+            let index = self
+                .pc
+                .checked_sub(TEXT_BASE)
+                .map(|offset| offset as usize / 4)
+                .filter(|&i| i < self.program.insts.len())
+                .ok_or_else(|| format!("jumped to invalid address {:#x}", self.pc))?;
+            self.pc += 4;
+            let program = self.program;
+            self.step(&program.insts[index])?;
+            self.regs[0] = 0;
+        }
+        Err(String::from("time limit exceeded"))
+    }
+
+    fn step(&mut self, inst: &Inst) -> Result<(), String> {
+        let r = self.regs;
+        match inst {
+            Inst::Li(rd, imm) => self.regs[*rd] = *imm,
+            Inst::La(rd, symbol) => self.regs[*rd] = self.symbol(symbol)? as i32,
+            Inst::Mv(rd, rs) => self.regs[*rd] = r[*rs],
+            Inst::Op(op, rd, rs1, rs2) => self.regs[*rd] = binary(op, r[*rs1], r[*rs2]),
+            Inst::OpImm(op, rd, rs, imm) => self.regs[*rd] = binary(op, r[*rs], *imm),
+            Inst::Unary(op, rd, rs) => {
+                self.regs[*rd] = match op.as_str() {
+                    "seqz" => (r[*rs] == 0) as i32,
+                    "snez" => (r[*rs] != 0) as i32,
+                    "neg" => r[*rs].wrapping_neg(),
+                    _ => !r[*rs],
+                }
+            }
+            Inst::Load(op, rd, offset, base) => {
+                let addr = r[*base].wrapping_add(*offset) as u32;
+                self.regs[*rd] = match op.as_str() {
+                    "lw" => i32::from_le_bytes(self.bytes(addr)?),
+                    "lh" => i16::from_le_bytes(self.bytes(addr)?) as i32,
+                    "lhu" => u16::from_le_bytes(self.bytes(addr)?) as i32,
+                    "lb" => i8::from_le_bytes(self.bytes(addr)?) as i32,
+                    _ => u8::from_le_bytes(self.bytes(addr)?) as i32,
+                }
+            }
+            Inst::Store(op, rs, offset, base) => {
+                let addr = r[*base].wrapping_add(*offset) as u32;
+                let bytes = r[*rs].to_le_bytes();
+                let len = match op.as_str() {
+                    "sw" => 4,
+                    "sh" => 2,
+                    _ => 1,
+                };
+                self.slice(addr, len)?.copy_from_slice(&bytes[..len]);
+            }
+            Inst::Branch(op, rs1, rs2, label) => {
+                let (a, b) = (r[*rs1], r[*rs2]);
+                let taken = match op.as_str() {
+                    "bnez" | "bne" => a != b,
+                    "beqz" | "beq" => a == b,
+                    "blt" => a < b,
+                    "bge" => a >= b,
+                    "bgt" => a > b,
+                    "ble" => a <= b,
+                    "bltu" => (a as u32) < b as u32,
+                    _ => a as u32 >= b as u32,
+                };
+                if taken {
+                    self.pc = self.symbol(label)?;
+                }
+            }
+            Inst::J(label) => self.pc = self.symbol(label)?,
+            Inst::Jr(rs) => self.pc = r[*rs] as u32,
+            Inst::Call(fun) => match self.program.symbols.get(fun) {
+                Some(&addr) => {
+                    self.regs[1] = self.pc as i32;
+                    self.pc = addr;
+                }
+                None => self.call_runtime(fun)?,
+            },
+            Inst::Ret => self.pc = r[1] as u32,
+        }
+        Ok(())
+    }
+
+    /// Calls a function of the SysY runtime library.
+    fn call_runtime(&mut self, fun: &str) -> Result<(), String> {
+        let (a0, a1) = (self.regs[10], self.regs[11]);
+        match fun {
+            "getint" => self.regs[10] = self.read_int(),
+            "getch" => {
+                self.regs[10] = match self.input.split_first() {
+                    Some((&c, rest)) => {
+                        self.input = rest;
+                        c as i32
+                    }
+                    None => -1,
+                }
+            }
+            "getarray" => {
+                let len = self.read_int();
+                for i in 0..len {
+                    let value = self.read_int();
+                    let addr = (a0 + 4 * i) as u32;
+                    self.slice(addr, 4)?.copy_from_slice(&value.to_le_bytes());
+                }
+                self.regs[10] = len;
+            }
+            "putint" => self.stdout.extend(a0.to_string().bytes()),
+            "putch" => self.stdout.push(a0 as u8),
+            "putarray" => {
+                self.stdout.extend(format!("{}:", a0).bytes());
+                for i in 0..a0 {
+                    let value = i32::from_le_bytes(self.bytes((a1 + 4 * i) as u32)?);
+                    self.stdout.extend(format!(" {}", value).bytes());
+                }
+                self.stdout.push(b'\n');
+            }
+            "putstr" => {
+                let mut addr = a0 as u32;
+                loop {
+                    let c = i32::from_le_bytes(self.bytes(addr)?);
+                    if c == 0 {
+                        break;
+                    }
+                    self.stdout.push(c as u8);
+                    addr += 4;
+                }
+            }
+            "starttime" | "stoptime" => {}
+            _ => return Err(format!("call to undefined function `{}`", fun)),
+        }
+        Ok(())
+    }
+
+    /// Reads a decimal integer from stdin, returns 0 at the end of input.
+    fn read_int(&mut self) -> i32 {
+        let text = std::str::from_utf8(self.input).unwrap_or("");
+        let trimmed = text.trim_start();
+        let len = trimmed
+            .char_indices()
+            .take_while(|&(i, c)| c.is_ascii_digit() || (i == 0 && c == '-'))
+            .count();
+        self.input = &self.input[text.len() - trimmed.len() + len..];
+        trimmed[..len].parse().unwrap_or(0)
+    }
+
+    fn symbol(&self, name: &str) -> Result<u32, String> {
+        self.program
+            .symbols
+            .get(name)
+            .copied()
+            .ok_or_else(|| format!("undefined symbol `{}`", name))
+    }
+
+    fn slice(&mut self, addr: u32, len: usize) -> Result<&mut [u8], String> {
+        let start = addr as usize;
+        if !addr.is_multiple_of(len as u32) {
+            return Err(format!("misaligned access at {:#x}", addr));
+        }
+        self.mem
+            .get_mut(start..start + len)
+            .filter(|_| start >= DATA_BASE as usize)
+            .ok_or_else(|| format!("invalid memory access at {:#x}", addr))
+    }
+
+    fn bytes<const N: usize>(&mut self, addr: u32) -> Result<[u8; N], String> {
+        Ok(self.slice(addr, N)?.try_into().unwrap())
+    }
+}
+
+fn binary(op: &str, a: i32, b: i32) -> i32 {
+    match op {
+        "add" => a.wrapping_add(b),
+        "sub" => a.wrapping_sub(b),
+        "mul" => a.wrapping_mul(b),
+        "mulhu" => ((a as u32 as u64 * b as u32 as u64) >> 32) as i32,
+        "div" if b == 0 => -1,
+        "div" => a.wrapping_div(b),
+        "divu" if b == 0 => -1,
+        "divu" => (a as u32 / b as u32) as i32,
+        "rem" if b == 0 => a,
+        "rem" => a.wrapping_rem(b),
+        "remu" if b == 0 => a,
+        "remu" => (a as u32 % b as u32) as i32,
+        "and" => a & b,
+        "or" => a | b,
+        "xor" => a ^ b,
+        "sll" => a.wrapping_shl(b as u32),
+        "srl" => (a as u32).wrapping_shr(b as u32) as i32,
+        "sra" => a.wrapping_shr(b as u32),
+        "slt" => (a < b) as i32,
+        "sltu" => ((a as u32) < b as u32) as i32,
+        "sgt" => (a > b) as i32,
+        _ => (a as u32 > b as u32) as i32,
+    }
+}
@@ -0,0 +1,51 @@
+//! Library tests: [`ophelia::compile`] stops after the requested stage.
+
+use ophelia::{Emit, Options, Output};
+
+const SOURCE: &str = "let main() => Int {\n  return 1 + 2;\n}\n";
+
+/// Compiles `SOURCE` and returns the textual form of the output.
+fn compile(emit: Emit) -> (Output, String) {
+    let options = Options {
+        emit,
+        ..Options::default()
+    };
+    let output = ophelia::compile(SOURCE, options).unwrap();
+    let mut text = Vec::new();
+    output.write_to(&mut text).unwrap();
+    (output, String::from_utf8(text).unwrap())
+}
+
+#[test]
+fn emit_ast() {
+    let (output, text) = compile(Emit::Ast);
+    assert!(matches!(output, Output::Ast(_)));
+    assert!(text.starts_with("CompUnit"));
+}
+
+#[test]
+fn emit_koopa() {
+    let (output, text) = compile(Emit::Koopa);
+    assert!(matches!(output, Output::Koopa(_)));
+    assert!(text.contains("fun @main(): i32"));
+}
+
+#[test]
+fn emit_riscv() {
+    let (output, text) = compile(Emit::Riscv);
+    assert!(matches!(output, Output::Riscv(_)));
+    assert!(text.contains("main:"));
+}
+
+#[test]
+fn errors() {
+    let options = Options {
+        file_name: String::from("bad.oph"),
+        ..Options::default()
+    };
+    let diagnostics = ophelia::compile("let main() => Int {\n  return;\n", options)
+        .err()
+        .unwrap();
+    assert_eq!(diagnostics.file_name(), "bad.oph");
+    assert_eq!(diagnostics.errors()[0].code, "E0019");
+}
@@ -0,0 +1,54 @@
+//! Session tests: every compilation owns its diagnostics, so compilations
+//! in the same process, and in parallel, do not affect each other.
+
+use ophelia::{Emit, Options};
+use std::thread;
+
+/// Compiles the given source, returns the reported error codes.
+fn error_codes(source: &str, file_name: &str) -> Vec<String> {
+    let options = Options {
+        emit: Emit::Koopa,
+        file_name: file_name.to_owned(),
+        ..Options::default()
+    };
+    match ophelia::compile(source, options) {
+        Ok(_) => Vec::new(),
+        Err(diagnostics) => {
+            assert_eq!(diagnostics.file_name(), file_name);
+            diagnostics
+                .errors()
+                .iter()
+                .map(|e| e.code.clone())
+                .collect()
+        }
+    }
+}
+
+#[test]
+fn sequential() {
+    let bad = "let main() => Int {\n  return x;\n}\n";
+    let good = "let main() => Int {\n  return 0;\n}\n";
+    assert_eq!(error_codes(bad, "bad.oph"), ["E0012"]);
+    assert!(error_codes(good, "good.oph").is_empty());
+    assert_eq!(error_codes(bad, "bad.oph"), ["E0012"]);
+}
+
+#[test]
+fn parallel() {
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                s.spawn(move || {
+                    // every thread reports a different number of errors
+                    let uses = "  putint(x);\n".repeat(i);
+                    let source = format!("let main() => Int {{\n{}  return 0;\n}}\n", uses);
+                    (i, error_codes(&source, &format!("{}.oph", i)))
+                })
+            })
+            .collect();
+        for handle in handles {
+            let (i, codes) = handle.join().unwrap();
+            assert_eq!(codes, vec![String::from("E0012"); i]);
+        }
+    });
+}